@@ -4,21 +4,61 @@ use avian3d::prelude::*;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
 
+use accuracy::Bloom;
+use attachment::AttachmentBonus;
+use definition::{BlasterStats, DefaultShotSounds, ShotSounds};
+use fire_mode::{FireMode, FireRate, TriggerState};
+use heat::Heat;
+use inventory::{Inventory, WeaponSwitch};
+use socket::{socket, ModelSockets, Sockets};
+use view_model::{Aim, ViewModel};
+
 use crate::{
-    health::Health,
-    map::Despawn,
+    health::{DamageEvent, DamageKind, Dead, Death, Health, Hitbox},
+    interact::InteractEvent,
+    map::{self, Cell, Despawn},
     player::{Player, PlayerAction, PlayerCam},
-    Layers,
 };
 
+mod accuracy;
+mod ammo_pickup;
+mod attachment;
+mod definition;
+mod fire_mode;
+mod heat;
+mod impact;
+mod inventory;
+mod projectile;
+mod reload;
+mod socket;
+mod view_model;
+
+pub use definition::sound_set;
+
 pub fn plugin(app: &mut App) {
     app.add_event::<BlasterEvent>()
         .register_type::<Ammo>()
         .register_type::<Blaster>()
         .register_type::<Recoil>()
-        .init_resource::<ShootSound>()
-        .add_systems(Update, (equip_gun, fire, recoil, hit_scan))
-        .add_systems(PostUpdate, pickup_gun_empty);
+        .register_type::<ThrowWhenEmpty>()
+        .add_systems(
+            Update,
+            (fire, recoil, hit_scan, report_kills, drop_gun, pickup_gun),
+        )
+        .add_plugins((
+            accuracy::plugin,
+            ammo_pickup::plugin,
+            attachment::plugin,
+            definition::plugin,
+            fire_mode::plugin,
+            heat::plugin,
+            impact::plugin,
+            inventory::plugin,
+            projectile::plugin,
+            reload::plugin,
+            socket::plugin,
+            view_model::plugin,
+        ));
 }
 
 #[derive(Component, Reflect, serde::Deserialize)]
@@ -29,129 +69,402 @@ pub struct Blaster;
 #[reflect(Deserialize, Component)]
 pub struct Ammo(u8);
 
+/// Blasters with this are thrown away when fired with no ammo left
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct ThrowWhenEmpty;
+
 #[derive(Component)]
 pub struct CurrentBlaster(Entity);
 
-fn pickup_gun_empty(
-    mut context: EventReader<CollisionStarted>,
+const THROW_SPEED: f32 = 6.;
+
+fn pickup_gun(
+    mut interactions: EventReader<InteractEvent>,
     mut commands: Commands,
-    blasters: Query<Entity, With<Blaster>>,
-    childern: Query<&Parent>,
-    player: Query<Entity, (With<Player>, Without<CurrentBlaster>)>,
+    blasters: Query<Option<&Children>, With<Blaster>>,
+    colliders: Query<(), With<Collider>>,
+    mut player: Query<(&mut Inventory, Has<CurrentBlaster>), With<Player>>,
+    holder: Query<Entity, With<ViewModel>>,
 ) {
-    let Ok(player) = player.get_single() else {
+    let Ok(holder) = holder.get_single() else {
         return;
     };
-    for colliding in context.read() {
-        let colliding = if colliding.0 == player {
-            colliding.1
-        } else if colliding.1 == player {
-            colliding.0
-        } else {
+    for InteractEvent {
+        player: entity,
+        target,
+    } in interactions.read()
+    {
+        let Ok(children) = blasters.get(*target) else {
             continue;
         };
-        if blasters.get(colliding).is_ok() {
-            commands.entity(colliding).remove::<RigidBody>();
-            commands.entity(colliding).remove::<Collider>();
-            commands.entity(colliding).insert((
-                CollisionLayers::new(Layers::Blasters, Layers::all_bits()),
-                RigidBody::Static,
-            ));
-            commands.entity(player).insert(CurrentBlaster(colliding));
-            return;
-        }
-        if let Ok(parent) = childern.get(colliding) {
-            if blasters.get(parent.get()).is_ok() {
-                commands.entity(parent.get()).remove::<RigidBody>();
-                commands.entity(colliding).remove::<Collider>();
-                commands
-                    .entity(colliding)
-                    .insert((CollisionLayers::new(Layers::Blasters, Layers::all_bits()),));
-                commands.entity(player).insert(CurrentBlaster(parent.get()));
-                return;
+        let Ok((mut inventory, holding)) = player.get_mut(*entity) else {
+            continue;
+        };
+        let Some(slot) = inventory.free_slot() else {
+            continue;
+        };
+        commands.entity(*target).remove::<(RigidBody, Collider)>();
+        // the collider is rebuilt from the cell if the blaster is dropped
+        for child in children.into_iter().flatten() {
+            if colliders.contains(*child) {
+                commands.entity(*child).despawn_recursive();
             }
         }
+        inventory.stow(&mut commands, holder, slot, *target);
+        if !holding {
+            commands
+                .entity(*entity)
+                .insert(WeaponSwitch::new(slot, false));
+        }
     }
 }
 
-fn equip_gun(
+/// Throws the held blaster when the player drops it or dies
+fn drop_gun(
     mut commands: Commands,
-    player: Query<(Entity, &CurrentBlaster), Changed<CurrentBlaster>>,
-    mut blaster: Query<(Entity, &mut Transform)>,
+    mut player: Query<(
+        Entity,
+        &ActionState<PlayerAction>,
+        &CurrentBlaster,
+        &mut Inventory,
+        &LinearVelocity,
+        Has<WeaponSwitch>,
+        Has<Dead>,
+    )>,
+    camera: Query<&GlobalTransform, With<PlayerCam>>,
+    blasters: Query<&Handle<Cell>>,
+    cells: Res<Assets<Cell>>,
+    mut blaster_event: EventWriter<BlasterEvent>,
 ) {
-    let Ok((player, equip)) = player.get_single() else {
+    let Ok((player, actions, gun, mut inventory, velocity, switching, dead)) =
+        player.get_single_mut()
+    else {
         return;
     };
-    let Ok((set, mut blaster)) = blaster.get_mut(equip.0) else {
-        error!("Current blaster has no transform");
+    if !dead && (switching || !actions.just_pressed(&PlayerAction::Drop)) {
+        return;
+    }
+    let Ok(camera) = camera.get_single() else {
         return;
     };
-    commands.entity(set).set_parent(player);
-    *blaster = Transform::from_translation(Vec3::NEG_Z)
-        .with_rotation(Quat::from_rotation_y(180f32.to_radians()));
+    // a switch still going would draw the next blaster into dead hands
+    if dead {
+        commands.entity(player).remove::<WeaponSwitch>();
+    }
+    inventory.remove(gun.0);
+    commands.entity(player).remove::<CurrentBlaster>();
+    blaster_event.send(BlasterEvent::Unequip {
+        shooter: player,
+        blaster: gun.0,
+    });
+    let mut blaster = commands.entity(gun.0);
+    blaster
+        .remove_parent_in_place()
+        .remove::<reload::Reloading>()
+        .insert((
+            LinearVelocity(camera.forward() * THROW_SPEED + velocity.0),
+            AngularVelocity(Vec3::X * 4.),
+        ));
+    match blasters.get(gun.0).ok().and_then(|cell| cells.get(cell)) {
+        Some(asset) => map::insert_physics(&mut blaster, asset),
+        None => {
+            error!("Dropped blaster has no cell");
+            blaster.insert(RigidBody::Dynamic);
+        }
+    }
 }
 
 #[derive(Component, Reflect, serde::Deserialize)]
 #[reflect(Deserialize, Component)]
 struct Recoil(f32);
 
-#[derive(Resource)]
-struct ShootSound(Vec<Handle<AudioSource>>);
+/// Everything that happens to a blaster, `shooter` is the entity holding it
+#[derive(Event)]
+pub enum BlasterEvent {
+    /// `direction` already has the blaster's spread applied
+    Fire {
+        shooter: Entity,
+        blaster: Entity,
+        origin: Vec3,
+        direction: Dir3,
+        /// World position of the blaster's muzzle, where projectiles and effects start
+        muzzle: Vec3,
+    },
+    /// A shot landing on `target`, `damage` is before the target's resistances and 0 when it has no [`Health`]
+    Hit {
+        shooter: Entity,
+        blaster: Entity,
+        target: Entity,
+        point: Vec3,
+        normal: Vec3,
+        damage: f32,
+        /// The [`Hitbox`] zone the shot landed in
+        zone: Option<String>,
+    },
+    /// The shooter's damage took the last of the target's [`Health`], `blaster` is the one it came from
+    Kill {
+        shooter: Entity,
+        blaster: Entity,
+        target: Entity,
+    },
+    /// The trigger was pulled with no ammo left
+    Empty {
+        shooter: Entity,
+        blaster: Entity,
+    },
+    Reload {
+        shooter: Entity,
+        blaster: Entity,
+    },
+    Equip {
+        shooter: Entity,
+        blaster: Entity,
+    },
+    Unequip {
+        shooter: Entity,
+        blaster: Entity,
+    },
+}
 
-impl ShootSound {
-    fn get(&self) -> Handle<AudioSource> {
-        use rand::seq::*;
-        self.0.choose(&mut rand::thread_rng()).cloned().unwrap()
+impl BlasterEvent {
+    /// Sends a hit on whatever the damage landed on
+    fn hit(
+        events: &mut EventWriter<BlasterEvent>,
+        shooter: Entity,
+        blaster: Entity,
+        (point, normal): (Vec3, Vec3),
+        damage: Damage,
+    ) {
+        events.send(BlasterEvent::Hit {
+            shooter,
+            blaster,
+            target: damage.target,
+            point,
+            normal,
+            damage: damage.amount,
+            zone: damage.zone,
+        });
     }
 }
 
-impl FromWorld for ShootSound {
-    fn from_world(world: &mut World) -> Self {
-        let mut sounds = Vec::new();
-        let server = world.resource::<AssetServer>();
-        for i in 0..5 {
-            sounds.push(server.load(format!("Sci-Fi-Sound/laserLarge_00{}.ogg", i)));
-            sounds.push(server.load(format!("Sci-Fi-Sound/laserRetro_00{}.ogg", i)));
-            sounds.push(server.load(format!("Sci-Fi-Sound/laserSmall_00{}.ogg", i)));
-        }
-        ShootSound(sounds)
+/// The result of damaging whatever owns a collider
+struct Damage {
+    target: Entity,
+    amount: f32,
+    zone: Option<String>,
+}
+
+fn report_kills(mut deaths: EventReader<Death>, mut blaster_event: EventWriter<BlasterEvent>) {
+    for Death {
+        target,
+        source,
+        weapon,
+    } in deaths.read()
+    {
+        let (Some(shooter), Some(blaster)) = (*source, *weapon) else {
+            continue;
+        };
+        blaster_event.send(BlasterEvent::Kill {
+            shooter,
+            blaster,
+            target: *target,
+        });
     }
 }
 
-#[derive(Event)]
-enum BlasterEvent {
-    Fire,
+/// The entity with [`Health`] that `collider` belongs to
+fn owner(
+    objects: &Query<(), (With<Health>, Without<Dead>)>,
+    parents: &Query<&Parent>,
+    collider: Entity,
+) -> Option<Entity> {
+    if objects.contains(collider) {
+        return Some(collider);
+    }
+    parents
+        .get(collider)
+        .ok()
+        .map(Parent::get)
+        .filter(|parent| objects.contains(*parent))
+}
+
+/// Applies `impulse` to `body` at `point`, which also spins it when off center
+///
+/// Does nothing to bodies that are not dynamic
+fn push(
+    impulses: &mut Query<(
+        &RigidBody,
+        &GlobalTransform,
+        &CenterOfMass,
+        &mut ExternalImpulse,
+    )>,
+    body: Entity,
+    point: Vec3,
+    impulse: Vec3,
+) {
+    let Ok((RigidBody::Dynamic, pos, center, mut external)) = impulses.get_mut(body) else {
+        return;
+    };
+    external.apply_impulse_at_point(impulse, point, pos.transform_point(center.0));
+}
+
+/// Damages whatever owns `collider`, scaled by its [`Hitbox`] if it has one
+fn damage_collider(
+    damage_events: &mut EventWriter<DamageEvent>,
+    objects: &Query<(), (With<Health>, Without<Dead>)>,
+    parents: &Query<&Parent>,
+    hitboxes: &Query<&Hitbox>,
+    collider: Entity,
+    (source, weapon): (Entity, Entity),
+    (amount, kind): (f32, DamageKind),
+) -> Damage {
+    let hitbox = hitboxes.get(collider).ok();
+    let zone = hitbox.map(|hitbox| hitbox.zone.clone());
+    let Some(target) = owner(objects, parents, collider) else {
+        return Damage {
+            target: collider,
+            amount: 0.,
+            zone,
+        };
+    };
+    let amount = hitbox.map_or(amount, |hitbox| hitbox.scale(amount));
+    damage_events.send(DamageEvent {
+        target,
+        source: Some(source),
+        weapon: Some(weapon),
+        amount,
+        kind,
+    });
+    Damage {
+        target,
+        amount,
+        zone,
+    }
 }
 
 fn fire(
     mut commands: Commands,
-    mut blasters: Query<(Entity, &mut Recoil, &mut Ammo), With<Blaster>>,
-    player: Query<(Entity, &ActionState<PlayerAction>, &CurrentBlaster)>,
-    sound: Res<ShootSound>,
+    mut blasters: Query<
+        (
+            Entity,
+            &mut Recoil,
+            Option<&mut Ammo>,
+            Option<&mut Heat>,
+            &mut TriggerState,
+            Option<&FireMode>,
+            Option<&FireRate>,
+            Option<&BlasterStats>,
+            Option<&AttachmentBonus>,
+            &Bloom,
+            Option<&ShotSounds>,
+            Has<ThrowWhenEmpty>,
+        ),
+        (With<Blaster>, Without<reload::Reloading>),
+    >,
+    mut player: Query<
+        (
+            Entity,
+            &ActionState<PlayerAction>,
+            &CurrentBlaster,
+            &mut Inventory,
+            &LinearVelocity,
+            &Aim,
+        ),
+        (Without<WeaponSwitch>, Without<Dead>),
+    >,
+    camera: Query<&GlobalTransform, With<PlayerCam>>,
+    muzzles: Query<(&GlobalTransform, Option<&ModelSockets>, Option<&Sockets>)>,
+    default_sounds: Res<DefaultShotSounds>,
     mut blaster_event: EventWriter<BlasterEvent>,
+    time: Res<Time>,
 ) {
-    let Ok((player_entity, player, gun)) = player.get_single() else {
+    let Ok((player_entity, player, gun, mut inventory, velocity, aim)) = player.get_single_mut()
+    else {
         return;
     };
-    if !player.just_pressed(&PlayerAction::Shoot) {
+    let Ok(camera) = camera.get_single() else {
         return;
-    }
-    let Ok((blaster, mut recoil, mut ammo)) = blasters.get_mut(gun.0) else {
+    };
+    let Ok((
+        blaster,
+        mut recoil,
+        ammo,
+        heat,
+        mut trigger,
+        mode,
+        rate,
+        stats,
+        bonus,
+        bloom,
+        sounds,
+        throw_when_empty,
+    )) = blasters.get_mut(gun.0)
+    else {
         return;
     };
-    if recoil.0 > 0. {
+    if ammo.is_none() && heat.is_none() {
+        return;
+    }
+    let curve = stats.map(|stats| stats.recoil).unwrap_or_default();
+    let mode = mode.copied().unwrap_or_default();
+    if !trigger.pull(mode, player, time.delta_seconds()) || trigger.cooldown > 0. {
+        return;
+    }
+    // without a fire rate the recoil animation is what limits the blaster
+    if rate.is_none() && recoil.0 > 0. {
+        return;
+    }
+    if heat.as_ref().is_some_and(|heat| heat.locked()) {
+        trigger.cancel();
         return;
     }
-    blaster_event.send(BlasterEvent::Fire);
-    recoil.0 += 1.;
-    commands.entity(blaster).insert(AudioSourceBundle {
-        source: sound.get(),
-        settings: PlaybackSettings {
-            mode: bevy::audio::PlaybackMode::Remove,
-            ..Default::default()
-        },
+    let empty = ammo.as_ref().is_some_and(|ammo| ammo.0 == 0);
+    if empty && !throw_when_empty {
+        trigger.cancel();
+        // only once per pull, not every frame an automatic trigger is held
+        if player.just_pressed(&PlayerAction::Shoot) {
+            blaster_event.send(BlasterEvent::Empty {
+                shooter: player_entity,
+                blaster,
+            });
+        }
+        return;
+    }
+    let speed = velocity.0.xz().length();
+    let cone = accuracy::cone(&shot_stats(stats, bonus), bloom, speed, aim.0);
+    let (muzzle, muzzle_offset) = muzzles
+        .get(blaster)
+        .map(|(pos, model, sockets)| {
+            let offset = socket(model, sockets, "muzzle").unwrap_or_default();
+            (pos.transform_point(offset), offset)
+        })
+        .unwrap_or((camera.translation(), Vec3::ZERO));
+    blaster_event.send(BlasterEvent::Fire {
+        shooter: player_entity,
+        blaster,
+        origin: camera.translation(),
+        direction: accuracy::spread(camera.forward(), cone),
+        muzzle,
+    });
+    trigger.fired(rate);
+    // fast blasters kick less so the recoil has recovered by the next shot
+    recoil.0 = rate.map_or(curve.kick, |rate| {
+        (rate.interval() * curve.recovery).min(curve.kick)
     });
-    if ammo.0 == 0 {
+    if let Some(audio) = sounds.unwrap_or(&default_sounds.0).bundle() {
+        commands
+            .spawn((
+                Name::new("Shot Sound"),
+                SpatialBundle::from_transform(Transform::from_translation(muzzle_offset)),
+                audio,
+            ))
+            .set_parent(blaster);
+    }
+    if let Some(mut heat) = heat {
+        heat.add_shot();
+    }
+    if empty {
         commands
             .entity(blaster)
             .remove::<Blaster>()
@@ -163,61 +476,137 @@ fn fire(
                 Despawn::new(5.),
             ));
         commands.entity(player_entity).remove::<CurrentBlaster>();
-    } else {
+        inventory.remove(blaster);
+        blaster_event.send(BlasterEvent::Unequip {
+            shooter: player_entity,
+            blaster,
+        });
+    } else if let Some(mut ammo) = ammo {
         ammo.0 -= 1;
     }
 }
 
-fn recoil(mut blasters: Query<(&mut Transform, &mut Recoil)>, time: Res<Time>) {
-    for (mut pos, mut recoil) in &mut blasters {
+fn recoil(
+    mut blasters: Query<(&mut Transform, &mut Recoil, Option<&BlasterStats>)>,
+    time: Res<Time>,
+) {
+    for (mut pos, mut recoil, stats) in &mut blasters {
         if recoil.0 <= 0. {
             continue;
         }
+        let recovery = stats.map_or(3., |stats| stats.recoil.recovery);
         let (_, yaw, roll) = pos.rotation.to_euler(EulerRot::XYZ);
-        recoil.0 -= time.delta_seconds() * 3.;
+        recoil.0 -= time.delta_seconds() * recovery;
         pos.rotation = Quat::from_euler(EulerRot::XYZ, recoil.0 + f32::consts::PI, yaw, roll);
     }
 }
 
+/// The stats a blaster shoots with once its attachments are counted
+fn shot_stats(stats: Option<&BlasterStats>, bonus: Option<&AttachmentBonus>) -> BlasterStats {
+    let mut stats = stats.cloned().unwrap_or_default();
+    if let Some(bonus) = bonus {
+        bonus.apply(&mut stats);
+    }
+    stats
+}
+
 fn hit_scan(
-    mut commands: Commands,
     mut gizmos: Gizmos,
-    mut objects: Query<(Entity, &mut Health)>,
+    spatial_query: SpatialQuery,
+    objects: Query<(), (With<Health>, Without<Dead>)>,
     parents: Query<&Parent>,
-    mut blaster_event: EventReader<BlasterEvent>,
-    player: Query<(&Parent, &GlobalTransform, &RayHits), With<PlayerCam>>,
+    hitboxes: Query<&Hitbox>,
+    colliders: Query<&ColliderParent>,
+    bodies: Query<&RigidBody>,
+    mut impulses: Query<(
+        &RigidBody,
+        &GlobalTransform,
+        &CenterOfMass,
+        &mut ExternalImpulse,
+    )>,
+    blasters: Query<
+        (Option<&BlasterStats>, Option<&AttachmentBonus>),
+        Without<projectile::Projectile>,
+    >,
+    mut blaster_event: ParamSet<(EventReader<BlasterEvent>, EventWriter<BlasterEvent>)>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
-    let (entity, player, rays) = player.single();
-    for event in blaster_event.read() {
-        match event {
-            BlasterEvent::Fire => {
-                gizmos.line(
-                    player.translation(),
-                    player.translation() + player.forward().as_vec3() * 10.,
-                    bevy::color::palettes::basic::RED,
-                );
-                for hit in rays.iter() {
-                    if let Ok((object, mut health)) = objects.get_mut(hit.entity) {
-                        if health.0 > 1 {
-                            health.0 -= 1;
-                        } else {
-                            commands.entity(object).remove::<Health>();
-                        }
-                        continue;
-                    }
-                    let Ok(parent) = parents.get(hit.entity) else {
-                        continue;
-                    };
-                    if let Ok((object, mut health)) = objects.get_mut(parent.get()) {
-                        if health.0 > 1 {
-                            health.0 -= 1;
-                        } else {
-                            commands.entity(object).remove::<Health>();
-                        }
-                        continue;
-                    }
+    let shots = blaster_event
+        .p0()
+        .read()
+        .filter_map(|event| match event {
+            BlasterEvent::Fire {
+                shooter,
+                blaster,
+                origin,
+                direction,
+                muzzle,
+            } => Some((*shooter, *blaster, *origin, *direction, *muzzle)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    for (shooter, blaster, origin, direction, muzzle) in shots {
+        let Ok((stats, bonus)) = blasters.get(blaster) else {
+            continue;
+        };
+        let stats = shot_stats(stats, bonus);
+        let mut hits = spatial_query.ray_hits(
+            origin,
+            direction,
+            stats.range,
+            u32::MAX,
+            true,
+            SpatialQueryFilter::from_excluded_entities([shooter]),
+        );
+        hits.sort_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact));
+        let mut penetration = stats.penetration;
+        let mut end = stats.range;
+        // a shot only damages each target once, through the first of its colliders it reaches
+        let mut damaged = Vec::new();
+        for hit in hits {
+            // static bodies always stop the shot, anything else uses up penetration
+            let blocking = colliders
+                .get(hit.entity)
+                .is_ok_and(|body| bodies.get(body.get()).is_ok_and(RigidBody::is_static));
+            let target = owner(&objects, &parents, hit.entity);
+            if target.is_some_and(|target| damaged.contains(&target)) {
+                if blocking {
+                    end = hit.time_of_impact;
+                    break;
                 }
+                continue;
+            }
+            damaged.extend(target);
+            let point = origin + direction * hit.time_of_impact;
+            if let Some(body) = colliders.get(hit.entity).ok().map(ColliderParent::get) {
+                push(&mut impulses, body, point, direction * stats.force);
+            }
+            let damage = damage_collider(
+                &mut damage_events,
+                &objects,
+                &parents,
+                &hitboxes,
+                hit.entity,
+                (shooter, blaster),
+                (stats.damage_at(hit.time_of_impact), DamageKind::Energy),
+            );
+            BlasterEvent::hit(
+                &mut blaster_event.p1(),
+                shooter,
+                blaster,
+                (point, hit.normal),
+                damage,
+            );
+            if blocking || penetration == 0 {
+                end = hit.time_of_impact;
+                break;
             }
+            penetration -= 1;
         }
+        gizmos.line(
+            muzzle,
+            origin + direction * end,
+            bevy::color::palettes::basic::RED,
+        );
     }
 }
@@ -0,0 +1,155 @@
+use core::f32;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{definition::BlasterStats, Blaster, BlasterEvent, CurrentBlaster};
+use crate::player::{Player, PlayerCam};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (setup_accuracy, kick_camera, recover_camera, cool_bloom),
+    );
+}
+
+/// Spread added by sustained fire, in degrees
+#[derive(Component, Default)]
+pub struct Bloom(f32);
+
+/// How far shots have turned the view, waiting to be recovered
+#[derive(Component, Default)]
+pub struct CameraRecoil {
+    /// Degrees as (yaw, pitch)
+    offset: Vec2,
+    /// Shots into the recoil pattern, starts over once the view has settled
+    shots: usize,
+}
+
+/// Half angle of the cone the next shot lands in, in degrees
+pub fn cone(stats: &BlasterStats, bloom: &Bloom, speed: f32, aim: f32) -> f32 {
+    let cone = stats.spread + bloom.0 + speed * stats.bloom.movement;
+    cone * 1f32.lerp(stats.bloom.aim, aim)
+}
+
+#[test]
+fn cone_narrows_when_still_and_aiming() {
+    let stats = BlasterStats {
+        spread: 1.,
+        ..Default::default()
+    };
+    let moving = cone(&stats, &Bloom(2.), 5., 0.);
+    let still = cone(&stats, &Bloom(2.), 0., 0.);
+    let aimed = cone(&stats, &Bloom(2.), 0., 1.);
+    assert!(moving > still && still > aimed);
+    assert_eq!(still, 3.);
+}
+
+/// Picks a random direction within `degrees` of `forward`
+pub fn spread(forward: Dir3, degrees: f32) -> Dir3 {
+    if degrees <= 0. {
+        return forward;
+    }
+    let mut rng = rand::thread_rng();
+    let tilt = Quat::from_axis_angle(
+        forward.any_orthonormal_vector(),
+        rng.gen_range(0. ..degrees).to_radians(),
+    );
+    let roll = Quat::from_axis_angle(*forward, rng.gen_range(0. ..f32::consts::TAU));
+    Dir3::new(roll * tilt * *forward).unwrap_or(forward)
+}
+
+fn setup_accuracy(
+    mut commands: Commands,
+    blasters: Query<Entity, (With<Blaster>, Without<Bloom>)>,
+    players: Query<Entity, Added<Player>>,
+) {
+    for blaster in &blasters {
+        commands.entity(blaster).insert(Bloom::default());
+    }
+    for player in &players {
+        commands.entity(player).insert(CameraRecoil::default());
+    }
+}
+
+/// Turns the view by `delta` degrees of (yaw, pitch)
+fn turn(body: &mut Transform, camera: &mut Transform, delta: Vec2) {
+    let (_, pitch, _) = camera.rotation.to_euler(EulerRot::YXZ);
+    camera.rotation = Quat::from_axis_angle(
+        Vec3::X,
+        (pitch + delta.y.to_radians()).clamp(-f32::consts::FRAC_PI_2, f32::consts::FRAC_PI_2),
+    );
+    let (yaw, _, _) = body.rotation.to_euler(EulerRot::YXZ);
+    body.rotation = Quat::from_axis_angle(Vec3::Y, yaw + delta.x.to_radians());
+}
+
+fn kick_camera(
+    mut blaster_event: EventReader<BlasterEvent>,
+    mut blasters: Query<(&mut Bloom, Option<&BlasterStats>)>,
+    mut player: Query<(&mut Transform, &mut CameraRecoil), With<Player>>,
+    mut camera: Query<&mut Transform, (With<PlayerCam>, Without<Player>)>,
+) {
+    let Ok((mut body, mut recoil)) = player.get_single_mut() else {
+        return;
+    };
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    for event in blaster_event.read() {
+        match event {
+            BlasterEvent::Fire { blaster, .. } => {
+                let Ok((mut bloom, stats)) = blasters.get_mut(*blaster) else {
+                    continue;
+                };
+                let stats = stats.cloned().unwrap_or_default();
+                bloom.0 = (bloom.0 + stats.bloom.per_shot).min(stats.bloom.max);
+                let pattern = &stats.camera.pattern;
+                let Some(kick) = pattern.get(recoil.shots.min(pattern.len().saturating_sub(1)))
+                else {
+                    continue;
+                };
+                recoil.shots += 1;
+                recoil.offset += *kick;
+                turn(&mut body, &mut camera, *kick);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn recover_camera(
+    mut player: Query<(&mut Transform, &mut CameraRecoil, Option<&CurrentBlaster>)>,
+    mut camera: Query<&mut Transform, (With<PlayerCam>, Without<CameraRecoil>)>,
+    blasters: Query<&BlasterStats>,
+    time: Res<Time>,
+) {
+    let Ok((mut body, mut recoil, current)) = player.get_single_mut() else {
+        return;
+    };
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    if recoil.offset == Vec2::ZERO {
+        recoil.shots = 0;
+        return;
+    }
+    let recovery = current
+        .and_then(|current| blasters.get(current.0).ok())
+        .map_or(BlasterStats::default().camera.recovery, |stats| {
+            stats.camera.recovery
+        });
+    let step = recoil
+        .offset
+        .clamp_length_max(recovery * time.delta_seconds());
+    recoil.offset -= step;
+    turn(&mut body, &mut camera, -step);
+}
+
+fn cool_bloom(mut blasters: Query<(&mut Bloom, Option<&BlasterStats>)>, time: Res<Time>) {
+    for (mut bloom, stats) in &mut blasters {
+        let decay = stats.map_or(BlasterStats::default().bloom.decay, |stats| {
+            stats.bloom.decay
+        });
+        bloom.0 = (bloom.0 - decay * time.delta_seconds()).max(0.);
+    }
+}
@@ -0,0 +1,112 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::{inventory::Inventory, reload::Magazine, Ammo, CurrentBlaster};
+use crate::{interact::InteractEvent, player::Player};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<AmmoType>()
+        .register_type::<AmmoPickup>()
+        .add_systems(PostUpdate, take_pickups);
+}
+
+/// Which [`AmmoPickup`]s a blaster can take ammo from
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct AmmoType(String);
+
+/// Gives `amount` rounds to a blaster with a matching [`AmmoType`]
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct AmmoPickup {
+    amount: u16,
+    tag: String,
+}
+
+/// Takes pickups the player interacts with or walks into
+///
+/// Both can happen to the same pickup in one frame, and so can several of its colliders touching,
+/// so each is only taken once before its despawn lands
+fn take_pickups(
+    mut commands: Commands,
+    mut interactions: EventReader<InteractEvent>,
+    mut contacts: EventReader<CollisionStarted>,
+    player: Query<(Entity, &Inventory, Option<&CurrentBlaster>), With<Player>>,
+    pickups: Query<&AmmoPickup>,
+    parents: Query<&Parent>,
+    mut blasters: Query<(&AmmoType, &mut Ammo, Option<&mut Magazine>)>,
+) {
+    let Ok((player, inventory, current)) = player.get_single() else {
+        return;
+    };
+    let pressed = interactions
+        .read()
+        .filter(|interaction| interaction.player == player)
+        .map(|interaction| interaction.target);
+    let touched = contacts
+        .read()
+        .filter_map(|CollisionStarted(a, b)| {
+            if *a == player {
+                Some(*b)
+            } else if *b == player {
+                Some(*a)
+            } else {
+                None
+            }
+        })
+        // pickups collide through a child collider
+        .filter_map(|other| {
+            std::iter::once(other)
+                .chain(parents.get(other).ok().map(Parent::get))
+                .find(|entity| pickups.contains(*entity))
+        });
+    let mut spent = Vec::new();
+    for target in pressed.chain(touched) {
+        if spent.contains(&target) {
+            continue;
+        }
+        spent.push(target);
+        let Ok(pickup) = pickups.get(target) else {
+            continue;
+        };
+        if refill(pickup, inventory, current, &mut blasters) {
+            commands.entity(target).despawn_recursive();
+        }
+    }
+}
+
+/// Gives the pickup to the held blaster if it matches, otherwise the first stowed one that does
+///
+/// Returns false when nothing the player carries can use it
+fn refill(
+    pickup: &AmmoPickup,
+    inventory: &Inventory,
+    current: Option<&CurrentBlaster>,
+    blasters: &mut Query<(&AmmoType, &mut Ammo, Option<&mut Magazine>)>,
+) -> bool {
+    let Some(blaster) = current
+        .map(|current| current.0)
+        .into_iter()
+        .chain(inventory.blasters())
+        .find(|blaster| {
+            blasters
+                .get(*blaster)
+                .is_ok_and(|(ammo_type, ..)| ammo_type.0 == pickup.tag)
+        })
+    else {
+        return false;
+    };
+    let Ok((_, mut ammo, magazine)) = blasters.get_mut(blaster) else {
+        return false;
+    };
+    match magazine {
+        Some(mut magazine) => magazine.add_reserve(pickup.amount),
+        // blasters without a magazine take the rounds straight away
+        None => {
+            ammo.0 = ammo
+                .0
+                .saturating_add(pickup.amount.min(u8::MAX as u16) as u8)
+        }
+    }
+    true
+}
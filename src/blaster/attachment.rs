@@ -0,0 +1,268 @@
+use avian3d::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{
+    definition::BlasterStats,
+    socket::{socket, ModelSockets, Sockets},
+    Blaster, CurrentBlaster,
+};
+use crate::{
+    interact::InteractEvent,
+    map::{Cell, MapCellBundle},
+    player::Player,
+};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Attachment>()
+        .register_type::<DefaultAttachments>()
+        .add_systems(
+            Update,
+            (
+                init_attached,
+                spawn_default_attachments,
+                attach_pending,
+                pickup_attachment,
+                move_attached,
+                prune_attached,
+                update_bonus,
+            )
+                .chain(),
+        );
+}
+
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct Attachment {
+    socket: String,
+    /// Replaces the blaster's zoom while aiming
+    #[serde(default)]
+    zoom: Option<f32>,
+    /// Multiplies the blaster's spread
+    #[serde(default = "one")]
+    spread: f32,
+    /// Extra rounds in the magazine
+    #[serde(default)]
+    magazine: u8,
+}
+
+fn one() -> f32 {
+    1.
+}
+
+/// Attachment cells a blaster is spawned with
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct DefaultAttachments(Vec<String>);
+
+/// What all the attachments on a blaster add up to
+#[derive(Component)]
+pub struct AttachmentBonus {
+    zoom: Option<f32>,
+    spread: f32,
+    pub magazine: u8,
+}
+
+impl Default for AttachmentBonus {
+    fn default() -> Self {
+        AttachmentBonus {
+            zoom: None,
+            spread: 1.,
+            magazine: 0,
+        }
+    }
+}
+
+impl AttachmentBonus {
+    pub fn apply(&self, stats: &mut BlasterStats) {
+        if let Some(zoom) = self.zoom {
+            stats.zoom = zoom;
+        }
+        stats.spread *= self.spread;
+    }
+}
+
+/// The attachment in each socket of a blaster
+#[derive(Component, Default)]
+struct Attached(HashMap<String, Entity>);
+
+/// Attaches a freshly spawned attachment cell once its components are in
+#[derive(Component)]
+struct AttachTo(Entity);
+
+fn init_attached(
+    mut commands: Commands,
+    blasters: Query<Entity, (With<Blaster>, Without<Attached>)>,
+) {
+    for blaster in &blasters {
+        commands.entity(blaster).insert(Attached::default());
+    }
+}
+
+fn spawn_default_attachments(
+    mut commands: Commands,
+    blasters: Query<(Entity, &DefaultAttachments), Added<DefaultAttachments>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (blaster, defaults) in &blasters {
+        for cell in defaults.0.iter() {
+            commands.spawn((
+                MapCellBundle {
+                    cell: asset_server.load(cell),
+                    ..Default::default()
+                },
+                AttachTo(blaster),
+            ));
+        }
+    }
+}
+
+/// Snaps `attachment` onto `blaster`, the caller is left to remove its colliders
+///
+/// The attachment stops being a cell of its own, so reloading the cell cannot give it its body back
+fn attach(
+    commands: &mut Commands,
+    (model, sockets): (Option<&ModelSockets>, Option<&Sockets>),
+    attached: &mut Attached,
+    blaster: Entity,
+    attachment: Entity,
+    info: &Attachment,
+) -> bool {
+    let Some(offset) = socket(model, sockets, &info.socket) else {
+        return false;
+    };
+    if attached.0.contains_key(&info.socket) {
+        return false;
+    }
+    attached.0.insert(info.socket.clone(), attachment);
+    commands
+        .entity(attachment)
+        .remove::<(RigidBody, Handle<Cell>)>()
+        .set_parent(blaster)
+        .insert(Transform::from_translation(offset));
+    true
+}
+
+fn attach_pending(
+    mut commands: Commands,
+    pending: Query<(Entity, &Attachment, &AttachTo, &Children), Added<Attachment>>,
+    colliders: Query<(), With<Collider>>,
+    mut blasters: Query<(Option<&ModelSockets>, Option<&Sockets>, &mut Attached)>,
+) {
+    for (entity, info, target, children) in &pending {
+        commands.entity(entity).remove::<AttachTo>();
+        let Ok((model, sockets, mut attached)) = blasters.get_mut(target.0) else {
+            continue;
+        };
+        if !attach(
+            &mut commands,
+            (model, sockets),
+            &mut attached,
+            target.0,
+            entity,
+            info,
+        ) {
+            warn!("Blaster has no free socket({}) for attachment", info.socket);
+            continue;
+        }
+        for child in children.iter().filter(|child| colliders.contains(**child)) {
+            commands.entity(*child).despawn_recursive();
+        }
+    }
+}
+
+fn pickup_attachment(
+    mut interactions: EventReader<InteractEvent>,
+    mut commands: Commands,
+    attachments: Query<(&Attachment, &Children), Without<AttachTo>>,
+    colliders: Query<(), With<Collider>>,
+    player: Query<&CurrentBlaster, With<Player>>,
+    mut blasters: Query<(Option<&ModelSockets>, Option<&Sockets>, &mut Attached)>,
+) {
+    for InteractEvent {
+        player: entity,
+        target,
+    } in interactions.read()
+    {
+        let Ok((info, children)) = attachments.get(*target) else {
+            continue;
+        };
+        let Ok(current) = player.get(*entity) else {
+            continue;
+        };
+        let Ok((model, sockets, mut attached)) = blasters.get_mut(current.0) else {
+            continue;
+        };
+        if !attach(
+            &mut commands,
+            (model, sockets),
+            &mut attached,
+            current.0,
+            *target,
+            info,
+        ) {
+            continue;
+        }
+        for child in children.iter().filter(|child| colliders.contains(**child)) {
+            commands.entity(*child).despawn_recursive();
+        }
+    }
+}
+
+/// Forgets attachments that were despawned, such as when the blaster's cell reloads,
+/// which also has [`update_bonus`] stop counting them
+fn prune_attached(mut removed: RemovedComponents<Attachment>, mut blasters: Query<&mut Attached>) {
+    let removed = removed.read().collect::<Vec<_>>();
+    if removed.is_empty() {
+        return;
+    }
+    for mut attached in &mut blasters {
+        if attached
+            .0
+            .values()
+            .any(|attachment| removed.contains(attachment))
+        {
+            attached
+                .0
+                .retain(|_, attachment| !removed.contains(attachment));
+        }
+    }
+}
+
+fn update_bonus(
+    mut commands: Commands,
+    blasters: Query<(Entity, &Attached), Changed<Attached>>,
+    attachments: Query<&Attachment>,
+) {
+    for (blaster, attached) in &blasters {
+        let mut bonus = AttachmentBonus::default();
+        for info in attached
+            .0
+            .values()
+            .filter_map(|attachment| attachments.get(*attachment).ok())
+        {
+            if let Some(zoom) = info.zoom {
+                bonus.zoom = Some(bonus.zoom.map_or(zoom, |current| current.max(zoom)));
+            }
+            bonus.spread *= info.spread;
+            bonus.magazine = bonus.magazine.saturating_add(info.magazine);
+        }
+        commands.entity(blaster).insert(bonus);
+    }
+}
+
+/// Keeps attachments on their sockets once the model's own sockets are found
+fn move_attached(
+    blasters: Query<(&Attached, Option<&ModelSockets>, Option<&Sockets>), Changed<ModelSockets>>,
+    mut attachments: Query<&mut Transform>,
+) {
+    for (attached, model, sockets) in &blasters {
+        for (name, attachment) in attached.0.iter() {
+            let Some(offset) = socket(model, sockets, name) else {
+                continue;
+            };
+            if let Ok(mut transform) = attachments.get_mut(*attachment) {
+                transform.translation = offset;
+            }
+        }
+    }
+}
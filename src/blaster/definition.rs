@@ -0,0 +1,365 @@
+use bevy::{audio::Volume, prelude::*};
+use rand::Rng;
+
+use super::fire_mode::{FireMode, FireRate};
+
+mod asset_loading;
+
+pub fn plugin(app: &mut App) {
+    app.init_asset_loader::<asset_loading::BlasterAssetLoader>()
+        .init_asset::<BlasterData>()
+        .register_type::<BlasterDefinition>()
+        .register_type::<BlasterStats>()
+        .register_type::<SoundSet>()
+        .init_resource::<DefaultShotSounds>()
+        .add_systems(
+            Update,
+            (
+                load_sound_set,
+                load_definition,
+                onchange_blaster,
+                onload_blaster,
+            )
+                .chain(),
+        );
+}
+
+/// Path to the `.blaster` file a blaster cell gets its stats from
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct BlasterDefinition(String);
+
+#[derive(Asset, Reflect)]
+pub struct BlasterData {
+    stats: BlasterStats,
+    fire_rate: Option<f32>,
+    fire_mode: Option<FireMode>,
+    sounds: Vec<Handle<AudioSource>>,
+}
+
+/// Which optional settings the blaster's definition inserted, so a reload can take back the ones it drops
+#[derive(Component, Clone, Copy, Default)]
+struct Defined {
+    fire_rate: bool,
+    fire_mode: bool,
+    sounds: bool,
+}
+
+/// The numbers behind a shot, blasters without one use [`BlasterStats::default`]
+#[derive(Component, Reflect, Clone)]
+pub struct BlasterStats {
+    pub damage: f32,
+    pub range: f32,
+    /// Half angle of the cone shots land in, in degrees
+    pub spread: f32,
+    pub recoil: RecoilCurve,
+    pub camera: CameraKick,
+    pub bloom: BloomCurve,
+    /// How many surfaces a hit scan shot can pass through before it stops
+    pub penetration: u8,
+    pub falloff: Option<Falloff>,
+    /// How much aiming down sights magnifies the view
+    pub zoom: f32,
+    /// Impulse a hit scan shot gives the body it hits, so heavier bodies move less
+    pub force: f32,
+}
+
+impl Default for BlasterStats {
+    fn default() -> Self {
+        BlasterStats {
+            damage: 1.,
+            range: 10.,
+            spread: 0.,
+            recoil: RecoilCurve::default(),
+            camera: CameraKick::default(),
+            bloom: BloomCurve::default(),
+            penetration: 0,
+            falloff: None,
+            zoom: 1.25,
+            force: 5.,
+        }
+    }
+}
+
+impl BlasterStats {
+    /// Damage dealt by a hit `distance` away from the shooter
+    pub fn damage_at(&self, distance: f32) -> f32 {
+        let Some(falloff) = self.falloff else {
+            return self.damage;
+        };
+        let t = ((distance - falloff.start) / (self.range - falloff.start).max(f32::EPSILON))
+            .clamp(0., 1.);
+        let scale = 1. + (falloff.min - 1.) * t;
+        self.damage * scale
+    }
+}
+
+#[test]
+fn damage_falloff() {
+    let stats = BlasterStats {
+        damage: 10.,
+        range: 30.,
+        falloff: Some(Falloff {
+            start: 10.,
+            min: 0.5,
+        }),
+        ..Default::default()
+    };
+    assert_eq!(stats.damage_at(5.), 10.);
+    assert_eq!(stats.damage_at(20.), 7.5);
+    assert_eq!(stats.damage_at(30.), 5.);
+    assert_eq!(stats.damage_at(100.), 5.);
+}
+
+/// Scales damage down from full at `start` to `min` times at the blaster's range
+#[derive(Reflect, Clone, Copy, serde::Deserialize)]
+pub struct Falloff {
+    pub start: f32,
+    pub min: f32,
+}
+
+#[derive(Reflect, Clone, Copy, serde::Deserialize)]
+pub struct RecoilCurve {
+    /// How far a shot tilts the blaster, in radians
+    pub kick: f32,
+    /// How fast the tilt recovers, in radians per second
+    pub recovery: f32,
+}
+
+impl Default for RecoilCurve {
+    fn default() -> Self {
+        RecoilCurve {
+            kick: 1.,
+            recovery: 3.,
+        }
+    }
+}
+
+/// How each shot kicks the player's view
+#[derive(Reflect, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct CameraKick {
+    /// Degrees each shot in a row turns the view as (yaw, pitch), the last step repeats once it runs out
+    pub pattern: Vec<Vec2>,
+    /// How fast the view settles back, in degrees per second
+    pub recovery: f32,
+}
+
+impl Default for CameraKick {
+    fn default() -> Self {
+        CameraKick {
+            pattern: vec![Vec2::new(0., 1.)],
+            recovery: 10.,
+        }
+    }
+}
+
+/// How the spread grows past [`BlasterStats::spread`], all in degrees
+#[derive(Reflect, Clone, Copy, serde::Deserialize)]
+#[serde(default)]
+pub struct BloomCurve {
+    /// Added by every shot
+    pub per_shot: f32,
+    /// Most spread sustained fire can add
+    pub max: f32,
+    /// How fast the added spread goes away, per second
+    pub decay: f32,
+    /// Added per meter per second the player is moving
+    pub movement: f32,
+    /// Multiplies the whole spread once fully aimed in
+    pub aim: f32,
+}
+
+impl Default for BloomCurve {
+    fn default() -> Self {
+        BloomCurve {
+            per_shot: 0.5,
+            max: 4.,
+            decay: 6.,
+            movement: 0.3,
+            aim: 0.3,
+        }
+    }
+}
+
+/// The `Sci-Fi-Sound` set a blaster shoots with, such as `laserSmall` with its `count` of 5 sounds
+#[derive(Component, Reflect, Clone, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct SoundSet {
+    name: String,
+    count: u8,
+}
+
+/// Paths of the `count` sounds in the set called `name`, the sets differ in size
+pub fn sound_set(name: &str, count: u8) -> impl Iterator<Item = String> + '_ {
+    (0..count).map(move |i| format!("Sci-Fi-Sound/{}_{:03}.ogg", name, i))
+}
+
+#[test]
+fn sound_set_paths() {
+    assert_eq!(
+        sound_set("doorOpen", 3).collect::<Vec<_>>(),
+        [
+            "Sci-Fi-Sound/doorOpen_000.ogg",
+            "Sci-Fi-Sound/doorOpen_001.ogg",
+            "Sci-Fi-Sound/doorOpen_002.ogg",
+        ]
+    );
+}
+
+/// The sounds a blaster picks from for each shot
+#[derive(Component)]
+pub struct ShotSounds(Vec<Handle<AudioSource>>);
+
+impl ShotSounds {
+    /// Plays a random sound from the set with a little pitch and volume variation
+    pub fn bundle(&self) -> Option<AudioBundle> {
+        use rand::seq::*;
+        let mut rng = rand::thread_rng();
+        let source = self.0.choose(&mut rng)?.clone();
+        Some(AudioBundle {
+            source,
+            settings: PlaybackSettings::DESPAWN
+                .with_spatial(true)
+                .with_speed(rng.gen_range(0.9..1.1))
+                .with_volume(Volume::new(rng.gen_range(0.8..1.))),
+        })
+    }
+}
+
+/// Used by blasters without their own [`ShotSounds`]
+#[derive(Resource)]
+pub struct DefaultShotSounds(pub ShotSounds);
+
+impl FromWorld for DefaultShotSounds {
+    fn from_world(world: &mut World) -> Self {
+        let server = world.resource::<AssetServer>();
+        DefaultShotSounds(ShotSounds(
+            sound_set("laserRetro", 5)
+                .map(|path| server.load(path))
+                .collect(),
+        ))
+    }
+}
+
+fn load_sound_set(
+    mut commands: Commands,
+    blasters: Query<(Entity, &SoundSet), Changed<SoundSet>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, set) in &blasters {
+        commands.entity(entity).insert(ShotSounds(
+            sound_set(&set.name, set.count)
+                .map(|path| asset_server.load(path))
+                .collect(),
+        ));
+    }
+}
+
+fn load_definition(
+    mut commands: Commands,
+    blasters: Query<(Entity, &BlasterDefinition), Changed<BlasterDefinition>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, definition) in &blasters {
+        commands
+            .entity(entity)
+            .insert(asset_server.load::<BlasterData>(&definition.0));
+    }
+}
+
+fn onchange_blaster(
+    mut commands: Commands,
+    blasters: Query<
+        (
+            Entity,
+            &Handle<BlasterData>,
+            Option<&Defined>,
+            Option<&SoundSet>,
+        ),
+        Changed<Handle<BlasterData>>,
+    >,
+    blaster_assets: Res<Assets<BlasterData>>,
+) {
+    for (blaster, handle, defined, set) in &blasters {
+        let Some(asset) = blaster_assets.get(handle.id()) else {
+            continue;
+        };
+        update_blaster(&mut commands, blaster, asset, defined, set);
+    }
+}
+
+fn onload_blaster(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<BlasterData>>,
+    blasters: Query<(
+        Entity,
+        &Handle<BlasterData>,
+        Option<&Defined>,
+        Option<&SoundSet>,
+    )>,
+    blaster_assets: Res<Assets<BlasterData>>,
+) {
+    for event in asset_events.read() {
+        match event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::LoadedWithDependencies { id } => {
+                for (blaster, handle, defined, set) in &blasters {
+                    if handle.id() == *id {
+                        let Some(asset) = blaster_assets.get(*id) else {
+                            error!("Blaster not in Assets<BlasterData> when loaded");
+                            continue;
+                        };
+                        update_blaster(&mut commands, blaster, asset, defined, set);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_blaster(
+    commands: &mut Commands,
+    target: Entity,
+    asset: &BlasterData,
+    defined: Option<&Defined>,
+    set: Option<&SoundSet>,
+) {
+    let defined = defined.copied().unwrap_or_default();
+    let mut blaster = commands.entity(target);
+    blaster.insert(asset.stats.clone());
+    match asset.fire_rate {
+        Some(rate) => {
+            blaster.insert(FireRate(rate));
+        }
+        None if defined.fire_rate => {
+            blaster.remove::<FireRate>();
+        }
+        None => {}
+    }
+    match asset.fire_mode {
+        Some(mode) => {
+            blaster.insert(mode);
+        }
+        None if defined.fire_mode => {
+            blaster.remove::<FireMode>();
+        }
+        None => {}
+    }
+    if !asset.sounds.is_empty() {
+        blaster.insert(ShotSounds(asset.sounds.clone()));
+    } else if defined.sounds {
+        blaster.remove::<ShotSounds>();
+        // the cell's own set gets loaded again
+        if let Some(set) = set {
+            blaster.insert(set.clone());
+        }
+    }
+    blaster.insert(Defined {
+        fire_rate: asset.fire_rate.is_some(),
+        fire_mode: asset.fire_mode.is_some(),
+        sounds: !asset.sounds.is_empty(),
+    });
+}
@@ -0,0 +1,113 @@
+use bevy::{
+    asset::{AssetLoader, AsyncReadExt},
+    prelude::*,
+};
+
+use super::{sound_set, BlasterData, BlasterStats, BloomCurve, CameraKick, Falloff, RecoilCurve};
+use crate::blaster::fire_mode::FireMode;
+
+#[derive(serde::Deserialize)]
+#[serde(default)]
+struct BlasterAsset {
+    damage: f32,
+    range: f32,
+    spread: f32,
+    recoil: RecoilCurve,
+    camera: CameraKick,
+    bloom: BloomCurve,
+    penetration: u8,
+    falloff: Option<Falloff>,
+    zoom: f32,
+    force: f32,
+    fire_rate: Option<f32>,
+    fire_mode: Option<FireMode>,
+    sounds: Vec<String>,
+    /// Adds every sound in the named set of the given size, see [`sound_set`]
+    sound_set: Option<(String, u8)>,
+}
+
+impl Default for BlasterAsset {
+    fn default() -> Self {
+        let stats = BlasterStats::default();
+        BlasterAsset {
+            damage: stats.damage,
+            range: stats.range,
+            spread: stats.spread,
+            recoil: stats.recoil,
+            camera: stats.camera,
+            bloom: stats.bloom,
+            penetration: stats.penetration,
+            falloff: stats.falloff,
+            zoom: stats.zoom,
+            force: stats.force,
+            fire_rate: None,
+            fire_mode: None,
+            sounds: Vec::new(),
+            sound_set: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct BlasterAssetLoader;
+
+impl AssetLoader for BlasterAssetLoader {
+    type Asset = BlasterData;
+    type Settings = ();
+    type Error = &'static str;
+    fn extensions(&self) -> &[&str] {
+        &["blaster"]
+    }
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut bevy::asset::io::Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> impl bevy::utils::ConditionalSendFuture<Output = Result<Self::Asset, Self::Error>> {
+        load_blaster_asset(reader, load_context)
+    }
+}
+
+async fn load_blaster_asset<'a>(
+    reader: &'a mut bevy::asset::io::Reader<'_>,
+    load_context: &'a mut bevy::asset::LoadContext<'_>,
+) -> Result<BlasterData, &'static str> {
+    let mut data = String::new();
+    if reader.read_to_string(&mut data).await.is_err() {
+        return Err("Failed to read string");
+    };
+    let blaster: BlasterAsset = match ron::from_str(&data) {
+        Ok(blaster) => blaster,
+        Err(e) => {
+            error!("{}", e);
+            return Err("Ron Failed");
+        }
+    };
+    Ok(BlasterData {
+        stats: BlasterStats {
+            damage: blaster.damage,
+            range: blaster.range,
+            spread: blaster.spread,
+            recoil: blaster.recoil,
+            camera: blaster.camera,
+            bloom: blaster.bloom,
+            penetration: blaster.penetration,
+            falloff: blaster.falloff,
+            zoom: blaster.zoom,
+            force: blaster.force,
+        },
+        fire_rate: blaster.fire_rate,
+        fire_mode: blaster.fire_mode,
+        sounds: blaster
+            .sounds
+            .into_iter()
+            .chain(
+                blaster
+                    .sound_set
+                    .iter()
+                    .flat_map(|(set, count)| sound_set(set, *count)),
+            )
+            .map(|sound| load_context.load(sound))
+            .collect(),
+    })
+}
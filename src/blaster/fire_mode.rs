@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use super::Blaster;
+use crate::player::PlayerAction;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<FireMode>()
+        .register_type::<FireRate>()
+        .add_systems(Update, init_trigger);
+}
+
+/// How holding the trigger turns into shots, blasters without one are [`FireMode::Semi`]
+#[derive(Component, Reflect, serde::Deserialize, Clone, Copy, Default)]
+#[reflect(Deserialize, Component)]
+pub enum FireMode {
+    /// One shot per press
+    #[default]
+    Semi,
+    /// `count` shots per press
+    Burst { count: u8 },
+    /// Keeps firing while held
+    Auto,
+    /// Fires on release once held for `time` seconds
+    Charge { time: f32 },
+}
+
+/// Rounds per minute, blasters with a fire rate are no longer limited by their recoil
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct FireRate(pub f32);
+
+impl FireRate {
+    pub fn interval(&self) -> f32 {
+        60. / self.0
+    }
+}
+
+#[derive(Component, Default)]
+pub struct TriggerState {
+    pub cooldown: f32,
+    burst: u8,
+    charge: f32,
+}
+
+impl TriggerState {
+    /// Returns true when `mode` wants to fire this frame
+    pub fn pull(
+        &mut self,
+        mode: FireMode,
+        actions: &ActionState<PlayerAction>,
+        delta: f32,
+    ) -> bool {
+        self.cooldown -= delta;
+        let pulled = match mode {
+            FireMode::Semi => actions.just_pressed(&PlayerAction::Shoot),
+            FireMode::Burst { count } => {
+                if self.burst == 0 && actions.just_pressed(&PlayerAction::Shoot) {
+                    self.burst = count;
+                }
+                self.burst > 0
+            }
+            FireMode::Auto => actions.pressed(&PlayerAction::Shoot),
+            FireMode::Charge { time } => {
+                if actions.pressed(&PlayerAction::Shoot) {
+                    self.charge += delta;
+                    false
+                } else if self.charge >= time {
+                    // a full charge keeps asking to fire until the shot actually goes out
+                    true
+                } else {
+                    self.charge = 0.;
+                    false
+                }
+            }
+        };
+        // time left over from the last shot only carries into the next while the trigger is held
+        if !pulled {
+            self.cooldown = self.cooldown.max(0.);
+        }
+        pulled
+    }
+
+    /// Call once a pull has turned into a shot
+    pub fn fired(&mut self, rate: Option<&FireRate>) {
+        self.burst = self.burst.saturating_sub(1);
+        self.charge = 0.;
+        // adding the interval keeps the rate steady when frames overshoot it,
+        // but a rate faster than the frame rate does not build up a backlog
+        if let Some(rate) = rate {
+            self.cooldown = (self.cooldown + rate.interval()).max(0.);
+        }
+    }
+
+    /// Stops the rest of a burst, used when the blaster runs dry
+    pub fn cancel(&mut self) {
+        self.burst = 0;
+        self.charge = 0.;
+        self.cooldown = self.cooldown.max(0.);
+    }
+}
+
+#[test]
+fn fire_rate_carries_over_frames() {
+    let mut trigger = TriggerState {
+        cooldown: -0.01,
+        ..Default::default()
+    };
+    trigger.fired(Some(&FireRate(600.)));
+    assert!((trigger.cooldown - 0.09).abs() < 1e-6);
+    trigger.cooldown = -0.05;
+    trigger.fired(Some(&FireRate(6000.)));
+    assert_eq!(trigger.cooldown, 0.);
+}
+
+fn init_trigger(
+    mut commands: Commands,
+    blasters: Query<Entity, (With<Blaster>, Without<TriggerState>)>,
+) {
+    for blaster in &blasters {
+        commands.entity(blaster).insert(TriggerState::default());
+    }
+}
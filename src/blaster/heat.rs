@@ -0,0 +1,106 @@
+use bevy::{audio::Volume, prelude::*};
+
+use super::definition::sound_set;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Heat>()
+        .init_resource::<HeatSounds>()
+        .add_systems(Update, cool_heat);
+}
+
+/// Lets a blaster fire without [`super::Ammo`], building heat instead until it has to vent
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct Heat {
+    /// Heat added by each shot, the blaster overheats at 1
+    per_shot: f32,
+    /// Heat lost per second
+    cooling: f32,
+    #[serde(skip)]
+    current: f32,
+    #[serde(skip)]
+    venting: bool,
+}
+
+impl Heat {
+    /// True while the blaster is too hot to fire
+    pub fn locked(&self) -> bool {
+        self.venting || self.current >= 1.
+    }
+
+    pub fn add_shot(&mut self) {
+        self.current = (self.current + self.per_shot).min(1.);
+    }
+}
+
+#[test]
+fn locks_once_overheated() {
+    let mut heat = Heat {
+        per_shot: 0.4,
+        cooling: 1.,
+        current: 0.,
+        venting: false,
+    };
+    heat.add_shot();
+    heat.add_shot();
+    assert!(!heat.locked());
+    heat.add_shot();
+    assert!(heat.locked());
+    assert_eq!(heat.current, 1.);
+}
+
+#[derive(Resource)]
+struct HeatSounds {
+    vent: Vec<Handle<AudioSource>>,
+    cooled: Vec<Handle<AudioSource>>,
+}
+
+impl FromWorld for HeatSounds {
+    fn from_world(world: &mut World) -> Self {
+        let server = world.resource::<AssetServer>();
+        HeatSounds {
+            vent: sound_set("thrusterFire", 5)
+                .map(|path| server.load(path))
+                .collect(),
+            cooled: sound_set("forceField", 5)
+                .map(|path| server.load(path))
+                .collect(),
+        }
+    }
+}
+
+fn cool_heat(
+    mut commands: Commands,
+    mut blasters: Query<(Entity, &mut Heat)>,
+    sounds: Res<HeatSounds>,
+    time: Res<Time>,
+) {
+    use rand::seq::*;
+    for (entity, mut heat) in &mut blasters {
+        let sound = if !heat.venting && heat.current >= 1. {
+            heat.venting = true;
+            &sounds.vent
+        } else if heat.venting && heat.current <= 0. {
+            heat.venting = false;
+            &sounds.cooled
+        } else {
+            heat.current = (heat.current - heat.cooling * time.delta_seconds()).max(0.);
+            continue;
+        };
+        let Some(source) = sound.choose(&mut rand::thread_rng()) else {
+            continue;
+        };
+        commands
+            .spawn((
+                Name::new("Heat Sound"),
+                SpatialBundle::default(),
+                AudioBundle {
+                    source: source.clone(),
+                    settings: PlaybackSettings::DESPAWN
+                        .with_spatial(true)
+                        .with_volume(Volume::new(0.6)),
+                },
+            ))
+            .set_parent(entity);
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{definition::sound_set, BlasterEvent};
+use crate::map::Despawn;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<ImpactAssets>()
+        .init_resource::<Decals>()
+        .add_systems(Update, (spawn_impacts, move_sparks));
+}
+
+/// Most decals that can be in the world, the oldest go first
+const MAX_DECALS: usize = 64;
+const SPARKS: usize = 8;
+const SPARK_SPEED: f32 = 3.;
+const DECAL_SIZE: f32 = 0.1;
+const DECAL_LIFE: f32 = 10.;
+
+#[derive(Resource)]
+struct ImpactAssets {
+    spark_mesh: Handle<Mesh>,
+    spark_material: Handle<StandardMaterial>,
+    decal_mesh: Handle<Mesh>,
+    decal_material: Handle<StandardMaterial>,
+    sounds: Vec<Handle<AudioSource>>,
+}
+
+impl FromWorld for ImpactAssets {
+    fn from_world(world: &mut World) -> Self {
+        let server = world.resource::<AssetServer>();
+        let sounds = sound_set("impactMetal", 5)
+            .map(|path| server.load(path))
+            .collect();
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let spark_mesh = meshes.add(Cuboid::from_length(0.02));
+        let decal_mesh = meshes.add(Rectangle::from_length(DECAL_SIZE));
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let spark_material = materials.add(StandardMaterial {
+            base_color: Color::srgb(1., 0.6, 0.1),
+            emissive: LinearRgba::rgb(4., 2., 0.4),
+            unlit: true,
+            ..Default::default()
+        });
+        let decal_material = materials.add(StandardMaterial {
+            base_color: Color::srgba(0.05, 0.05, 0.05, 0.8),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..Default::default()
+        });
+        ImpactAssets {
+            spark_mesh,
+            spark_material,
+            decal_mesh,
+            decal_material,
+            sounds,
+        }
+    }
+}
+
+/// Decals in the order they were made
+#[derive(Resource, Default)]
+struct Decals(VecDeque<Entity>);
+
+#[derive(Component)]
+struct Decal;
+
+#[derive(Component)]
+struct Spark(Vec3);
+
+fn spawn_impacts(
+    mut commands: Commands,
+    mut blaster_event: EventReader<BlasterEvent>,
+    mut decals: ResMut<Decals>,
+    existing: Query<(), With<Decal>>,
+    colliders: Query<&ColliderParent>,
+    bodies: Query<&RigidBody>,
+    assets: Res<ImpactAssets>,
+) {
+    use rand::seq::*;
+    let mut rng = rand::thread_rng();
+    decals.0.retain(|decal| existing.contains(*decal));
+    for event in blaster_event.read() {
+        let BlasterEvent::Hit {
+            target,
+            point,
+            normal,
+            ..
+        } = event
+        else {
+            continue;
+        };
+        let Ok(normal) = Dir3::new(*normal) else {
+            continue;
+        };
+        for _ in 0..SPARKS {
+            let scatter = Vec3::new(
+                rng.gen_range(-1. ..1.),
+                rng.gen_range(-1. ..1.),
+                rng.gen_range(-1. ..1.),
+            );
+            commands.spawn((
+                Name::new("Spark"),
+                PbrBundle {
+                    mesh: assets.spark_mesh.clone(),
+                    material: assets.spark_material.clone(),
+                    transform: Transform::from_translation(*point),
+                    ..Default::default()
+                },
+                Spark((*normal + scatter * 0.8).normalize_or_zero() * SPARK_SPEED),
+                Despawn::new(rng.gen_range(0.2..0.4)),
+            ));
+        }
+        if let Some(sound) = assets.sounds.choose(&mut rng) {
+            commands.spawn((
+                Name::new("Impact Sound"),
+                SpatialBundle::from_transform(Transform::from_translation(*point)),
+                AudioBundle {
+                    source: sound.clone(),
+                    settings: PlaybackSettings::DESPAWN.with_spatial(true),
+                },
+            ));
+        }
+        // only static surfaces get a decal, anything else could move out from under it
+        let body = colliders.get(*target).map_or(*target, ColliderParent::get);
+        if !bodies.get(body).is_ok_and(RigidBody::is_static) {
+            continue;
+        }
+        let decal = commands
+            .spawn((
+                Name::new("Decal"),
+                PbrBundle {
+                    mesh: assets.decal_mesh.clone(),
+                    material: assets.decal_material.clone(),
+                    // sit the decal just off the surface so it does not z-fight
+                    transform: Transform::from_translation(*point + *normal * 0.005)
+                        .with_rotation(Quat::from_rotation_arc(Vec3::Z, *normal)),
+                    ..Default::default()
+                },
+                Decal,
+                Despawn::new(DECAL_LIFE),
+            ))
+            .id();
+        decals.0.push_back(decal);
+        while decals.0.len() > MAX_DECALS {
+            if let Some(old) = decals.0.pop_front() {
+                commands.entity(old).despawn_recursive();
+            }
+        }
+    }
+}
+
+fn move_sparks(mut sparks: Query<(&mut Transform, &mut Spark)>, time: Res<Time>) {
+    for (mut pos, mut spark) in &mut sparks {
+        spark.0.y -= 9.81 * time.delta_seconds();
+        pos.translation += spark.0 * time.delta_seconds();
+    }
+}
@@ -0,0 +1,246 @@
+use core::f32;
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{reload::Reloading, BlasterEvent, CurrentBlaster};
+use crate::{
+    health::Dead,
+    map::{MapRules, RespawnPlayer},
+    player::{Player, PlayerAction},
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            setup_inventory,
+            reset_inventory,
+            select_weapon,
+            switch_weapon,
+            equip_gun,
+        )
+            .chain(),
+    );
+}
+
+const SLOTS: usize = 3;
+const HOLSTER_TIME: f32 = 0.2;
+const DRAW_TIME: f32 = 0.25;
+/// How far the blaster drops while being holstered
+const HOLSTER_DIP: f32 = 0.6;
+
+/// The blasters a player is carrying
+#[derive(Component)]
+pub struct Inventory {
+    slots: Vec<Option<Entity>>,
+    current: usize,
+}
+
+impl Inventory {
+    pub fn new(size: usize) -> Inventory {
+        Inventory {
+            slots: vec![None; size],
+            current: 0,
+        }
+    }
+
+    pub fn free_slot(&self) -> Option<usize> {
+        self.slots.iter().position(Option::is_none)
+    }
+
+    /// Puts `blaster` in `slot` and parents it to the hidden `holder`
+    pub fn stow(&mut self, commands: &mut Commands, holder: Entity, slot: usize, blaster: Entity) {
+        self.slots[slot] = Some(blaster);
+        commands.entity(blaster).set_parent(holder).insert((
+            Transform::from_rotation(Quat::from_rotation_y(f32::consts::PI)),
+            Visibility::Hidden,
+        ));
+    }
+
+    pub fn blasters(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.slots.iter().flatten().copied()
+    }
+
+    pub fn remove(&mut self, blaster: Entity) {
+        for slot in self.slots.iter_mut() {
+            if *slot == Some(blaster) {
+                *slot = None;
+            }
+        }
+    }
+
+    /// The next filled slot `step` away from the current one
+    fn cycle(&self, step: isize) -> Option<usize> {
+        let len = self.slots.len() as isize;
+        (1..=len)
+            .map(|i| (self.current as isize + step * i).rem_euclid(len) as usize)
+            .find(|slot| self.slots[*slot].is_some())
+    }
+}
+
+/// Holsters the current blaster then draws the one in `target`
+#[derive(Component)]
+pub struct WeaponSwitch {
+    target: usize,
+    time: f32,
+    swapped: bool,
+}
+
+impl WeaponSwitch {
+    /// Skips the holster when there is nothing in hand
+    pub fn new(target: usize, holster: bool) -> WeaponSwitch {
+        WeaponSwitch {
+            target,
+            time: if holster { 0. } else { HOLSTER_TIME },
+            swapped: false,
+        }
+    }
+
+    pub fn dip(&self) -> f32 {
+        if self.time < HOLSTER_TIME {
+            self.time / HOLSTER_TIME * HOLSTER_DIP
+        } else {
+            (1. - (self.time - HOLSTER_TIME) / DRAW_TIME).max(0.) * HOLSTER_DIP
+        }
+    }
+}
+
+fn setup_inventory(mut commands: Commands, players: Query<Entity, Added<Player>>) {
+    for player in &players {
+        commands.entity(player).insert(Inventory::new(SLOTS));
+    }
+}
+
+/// Throws away everything a player carries when they respawn after dying, unless the map lets them keep it
+///
+/// Arriving on a new map keeps the inventory
+fn reset_inventory(
+    mut commands: Commands,
+    mut respawn: EventReader<RespawnPlayer>,
+    mut players: Query<&mut Inventory>,
+    rules: Res<MapRules>,
+) {
+    for RespawnPlayer { player, died } in respawn.read() {
+        if !died || rules.keep_inventory {
+            continue;
+        }
+        let Ok(mut inventory) = players.get_mut(*player) else {
+            continue;
+        };
+        for blaster in inventory.slots.iter_mut().filter_map(Option::take) {
+            commands.entity(blaster).despawn_recursive();
+        }
+        inventory.current = 0;
+        commands
+            .entity(*player)
+            .remove::<(CurrentBlaster, WeaponSwitch)>();
+    }
+}
+
+fn select_weapon(
+    mut commands: Commands,
+    player: Query<
+        (
+            Entity,
+            &ActionState<PlayerAction>,
+            &Inventory,
+            Option<&CurrentBlaster>,
+        ),
+        (Without<WeaponSwitch>, Without<Dead>),
+    >,
+) {
+    for (entity, actions, inventory, current) in &player {
+        let holding = current.is_some();
+        let target = if actions.just_pressed(&PlayerAction::NextWeapon) {
+            inventory.cycle(1)
+        } else if actions.just_pressed(&PlayerAction::PrevWeapon) {
+            inventory.cycle(-1)
+        } else {
+            [
+                PlayerAction::Slot1,
+                PlayerAction::Slot2,
+                PlayerAction::Slot3,
+            ]
+            .iter()
+            .position(|slot| actions.just_pressed(slot))
+        };
+        let Some(target) = target else {
+            continue;
+        };
+        if inventory.slots.get(target).copied().flatten().is_none() {
+            continue;
+        }
+        if holding && target == inventory.current {
+            continue;
+        }
+        commands
+            .entity(entity)
+            .insert(WeaponSwitch::new(target, holding));
+        // a reload does not carry on while the blaster is being put away
+        if let Some(current) = current {
+            commands.entity(current.0).remove::<Reloading>();
+        }
+    }
+}
+
+fn switch_weapon(
+    mut commands: Commands,
+    mut player: Query<(
+        Entity,
+        &mut Inventory,
+        &mut WeaponSwitch,
+        Option<&CurrentBlaster>,
+    )>,
+    mut blaster_event: EventWriter<BlasterEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut inventory, mut switch, current) in &mut player {
+        switch.time += time.delta_seconds();
+        if !switch.swapped && switch.time >= HOLSTER_TIME {
+            switch.swapped = true;
+            inventory.current = switch.target;
+            if let Some(current) = current {
+                blaster_event.send(BlasterEvent::Unequip {
+                    shooter: entity,
+                    blaster: current.0,
+                });
+            }
+            match inventory.slots[switch.target] {
+                Some(blaster) => {
+                    blaster_event.send(BlasterEvent::Equip {
+                        shooter: entity,
+                        blaster,
+                    });
+                    commands.entity(entity).insert(CurrentBlaster(blaster))
+                }
+                None => commands.entity(entity).remove::<CurrentBlaster>(),
+            };
+        }
+        if switch.time >= HOLSTER_TIME + DRAW_TIME {
+            commands.entity(entity).remove::<WeaponSwitch>();
+        }
+    }
+}
+
+fn equip_gun(
+    mut commands: Commands,
+    player: Query<(&CurrentBlaster, &Inventory), Changed<CurrentBlaster>>,
+    mut blasters: Query<(&mut Visibility, &mut Transform)>,
+) {
+    for (equip, inventory) in &player {
+        for blaster in inventory.slots.iter().flatten() {
+            let Ok((mut visibility, mut transform)) = blasters.get_mut(*blaster) else {
+                error!("Stowed blaster has no transform");
+                continue;
+            };
+            commands.entity(*blaster).remove::<Reloading>();
+            if *blaster != equip.0 {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+            *visibility = Visibility::Inherited;
+            *transform = Transform::from_rotation(Quat::from_rotation_y(f32::consts::PI));
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    attachment::AttachmentBonus, damage_collider, definition::BlasterStats, shot_stats,
+    BlasterEvent,
+};
+use crate::{
+    health::{DamageEvent, DamageKind, Dead, Health, Hitbox},
+    map::Despawn,
+    Layers,
+};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Projectile>()
+        .add_systems(Update, (spawn_darts, dart_life))
+        .add_systems(PostUpdate, dart_contact);
+}
+
+/// Makes a blaster fire physical darts instead of using hit scan
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct Projectile {
+    scene: String,
+    speed: f32,
+    #[serde(default = "five")]
+    life: f32,
+}
+
+fn five() -> f32 {
+    5.
+}
+
+#[derive(Component)]
+struct Dart {
+    life: f32,
+    damage: f32,
+    shooter: Entity,
+    blaster: Entity,
+}
+
+fn spawn_darts(
+    mut commands: Commands,
+    mut blaster_event: EventReader<BlasterEvent>,
+    blasters: Query<(&Projectile, Option<&BlasterStats>, Option<&AttachmentBonus>)>,
+    asset_server: Res<AssetServer>,
+) {
+    for event in blaster_event.read() {
+        match event {
+            BlasterEvent::Fire {
+                shooter,
+                blaster,
+                direction,
+                muzzle,
+                ..
+            } => {
+                let Ok((projectile, stats, bonus)) = blasters.get(*blaster) else {
+                    continue;
+                };
+                let stats = shot_stats(stats, bonus);
+                let direction = direction.as_vec3();
+                commands.spawn((
+                    Name::new("Dart"),
+                    SceneBundle {
+                        scene: asset_server.load(&projectile.scene),
+                        // the dart models are modeled along the Y axis
+                        transform: Transform::from_translation(*muzzle)
+                            .with_rotation(Quat::from_rotation_arc(Vec3::Y, direction)),
+                        ..Default::default()
+                    },
+                    RigidBody::Dynamic,
+                    Collider::capsule(0.01, 0.04),
+                    SweptCcd::default(),
+                    LinearVelocity(direction * projectile.speed),
+                    CollisionLayers::new(Layers::Projectiles, Layers::all_bits()),
+                    Dart {
+                        life: projectile.life,
+                        damage: stats.damage,
+                        shooter: *shooter,
+                        blaster: *blaster,
+                    },
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn dart_life(mut commands: Commands, mut darts: Query<(Entity, &mut Dart)>, time: Res<Time>) {
+    for (entity, mut dart) in &mut darts {
+        dart.life -= time.delta_seconds();
+        if dart.life <= 0. {
+            commands
+                .entity(entity)
+                .remove::<Dart>()
+                .insert(Despawn::new(1.));
+        }
+    }
+}
+
+fn dart_contact(
+    mut commands: Commands,
+    mut contacts: EventReader<CollisionStarted>,
+    darts: Query<(&Dart, &Transform, &LinearVelocity)>,
+    objects: Query<(), (With<Health>, Without<Dead>)>,
+    parents: Query<&Parent>,
+    hitboxes: Query<&Hitbox>,
+    mut blaster_event: EventWriter<BlasterEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    // a dart can touch a body and several of its hitboxes in the same step
+    let mut touched: Vec<(Entity, Vec<Entity>)> = Vec::new();
+    for CollisionStarted(a, b) in contacts.read() {
+        let (dart, other) = if darts.contains(*a) {
+            (*a, *b)
+        } else if darts.contains(*b) {
+            (*b, *a)
+        } else {
+            continue;
+        };
+        match touched.iter_mut().find(|(touched, _)| *touched == dart) {
+            Some((_, others)) => others.push(other),
+            None => touched.push((dart, vec![other])),
+        }
+    }
+    for (dart, others) in touched {
+        // hitboxes win over the plain collider, and the one scaling damage the most over the rest
+        let Some(other) = others.into_iter().max_by(|a, b| {
+            let a = hitboxes.get(*a).ok().map(|hitbox| hitbox.multiplier);
+            let b = hitboxes.get(*b).ok().map(|hitbox| hitbox.multiplier);
+            match (a, b) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => a.is_some().cmp(&b.is_some()),
+            }
+        }) else {
+            continue;
+        };
+        let Ok((stats, pos, velocity)) = darts.get(dart) else {
+            continue;
+        };
+        commands
+            .entity(dart)
+            .remove::<Dart>()
+            .insert(Despawn::new(1.));
+        let damage = damage_collider(
+            &mut damage_events,
+            &objects,
+            &parents,
+            &hitboxes,
+            other,
+            (stats.shooter, stats.blaster),
+            (stats.damage, DamageKind::Kinetic),
+        );
+        // darts do not know the contact normal so face back along their flight
+        BlasterEvent::hit(
+            &mut blaster_event,
+            stats.shooter,
+            stats.blaster,
+            (pos.translation, -velocity.0.normalize_or_zero()),
+            damage,
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use core::f32;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    attachment::AttachmentBonus, inventory::WeaponSwitch, Ammo, BlasterEvent, CurrentBlaster,
+    Recoil,
+};
+use crate::{health::Dead, map::Despawn, player::PlayerAction, Layers};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Magazine>()
+        .add_systems(Update, (start_reload, reload));
+}
+
+/// Lets a blaster refill its [`Ammo`] from a reserve
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct Magazine {
+    size: u8,
+    reserve: u16,
+    reload_time: f32,
+}
+
+impl Magazine {
+    pub fn add_reserve(&mut self, amount: u16) {
+        self.reserve = self.reserve.saturating_add(amount);
+    }
+
+    /// Rounds the magazine holds once attachments are counted
+    fn capacity(&self, bonus: Option<&AttachmentBonus>) -> u8 {
+        self.size
+            .saturating_add(bonus.map_or(0, |bonus| bonus.magazine))
+    }
+}
+
+#[derive(Component)]
+pub struct Reloading {
+    time: f32,
+    length: f32,
+}
+
+fn start_reload(
+    mut commands: Commands,
+    player: Query<
+        (Entity, &ActionState<PlayerAction>, &CurrentBlaster),
+        (Without<WeaponSwitch>, Without<Dead>),
+    >,
+    blasters: Query<
+        (
+            &Recoil,
+            &Ammo,
+            &Magazine,
+            Option<&AttachmentBonus>,
+            &GlobalTransform,
+        ),
+        Without<Reloading>,
+    >,
+    asset_server: Res<AssetServer>,
+    mut blaster_event: EventWriter<BlasterEvent>,
+) {
+    let Ok((shooter, actions, gun)) = player.get_single() else {
+        return;
+    };
+    if !actions.just_pressed(&PlayerAction::Reload) {
+        return;
+    }
+    let Ok((recoil, ammo, magazine, bonus, pos)) = blasters.get(gun.0) else {
+        return;
+    };
+    if recoil.0 > 0. || ammo.0 >= magazine.capacity(bonus) || magazine.reserve == 0 {
+        return;
+    }
+    commands.entity(gun.0).insert(Reloading {
+        time: 0.,
+        length: magazine.reload_time,
+    });
+    blaster_event.send(BlasterEvent::Reload {
+        shooter,
+        blaster: gun.0,
+    });
+    let (_, rotation, translation) = pos.to_scale_rotation_translation();
+    commands.spawn((
+        Name::new("Ejected Clip"),
+        SceneBundle {
+            scene: asset_server.load("Blasters/clipA.glb#Scene0"),
+            transform: Transform::from_translation(translation).with_rotation(rotation),
+            ..Default::default()
+        },
+        RigidBody::Dynamic,
+        Collider::cuboid(0.06, 0.24, 0.14),
+        LinearVelocity(Vec3::NEG_Y * 2.),
+        AngularVelocity(Vec3::X * 3.),
+        CollisionLayers::new(Layers::Blasters, Layers::Ground),
+        Despawn::new(3.),
+    ));
+}
+
+fn reload(
+    mut commands: Commands,
+    mut blasters: Query<(
+        Entity,
+        &mut Transform,
+        &mut Reloading,
+        &mut Ammo,
+        &mut Magazine,
+        Option<&AttachmentBonus>,
+    )>,
+    time: Res<Time>,
+) {
+    for (entity, mut pos, mut reloading, mut ammo, mut magazine, bonus) in &mut blasters {
+        reloading.time += time.delta_seconds();
+        let progress = (reloading.time / reloading.length).min(1.);
+        // dip the blaster down and back up over the length of the reload
+        let dip = (progress * f32::consts::PI).sin() * 0.8;
+        let (_, yaw, roll) = pos.rotation.to_euler(EulerRot::XYZ);
+        pos.rotation = Quat::from_euler(EulerRot::XYZ, f32::consts::PI - dip, yaw, roll);
+        if progress < 1. {
+            continue;
+        }
+        let take = (magazine.capacity(bonus).saturating_sub(ammo.0) as u16).min(magazine.reserve);
+        magazine.reserve -= take;
+        ammo.0 += take as u8;
+        commands.entity(entity).remove::<Reloading>();
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::{prelude::*, scene::SceneInstanceReady, utils::HashMap};
+
+use super::Blaster;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Sockets>()
+        .add_systems(Update, find_model_sockets);
+}
+
+/// Names of the nodes in a blaster model that are used as sockets
+const NODES: [&str; 4] = ["muzzle", "grip", "sight", "magazine"];
+
+/// Named points on a blaster relative to the blaster, used when the model has no node of that name
+///
+/// `muzzle` is where shots leave, `grip` is held at the view model and `sight` lines up with the camera while aiming,
+/// anything else is for attachments
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct Sockets(HashMap<String, Vec3>);
+
+/// Sockets found as named nodes in the blaster's model
+#[derive(Component, Default)]
+pub struct ModelSockets(HashMap<String, Vec3>);
+
+/// Where the socket called `name` is on a blaster, nodes in the model win over the cell's offsets
+pub fn socket(model: Option<&ModelSockets>, cell: Option<&Sockets>, name: &str) -> Option<Vec3> {
+    model
+        .and_then(|model| model.0.get(name))
+        .or_else(|| cell.and_then(|cell| cell.0.get(name)))
+        .copied()
+}
+
+fn find_model_sockets(
+    mut commands: Commands,
+    mut ready: EventReader<SceneInstanceReady>,
+    blasters: Query<(), With<Blaster>>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    transforms: Query<&Transform>,
+    parents: Query<&Parent>,
+) {
+    for SceneInstanceReady { parent } in ready.read() {
+        if !blasters.contains(*parent) {
+            continue;
+        }
+        let mut sockets = ModelSockets::default();
+        for node in children.iter_descendants(*parent) {
+            let Ok(name) = names.get(node) else {
+                continue;
+            };
+            if NODES.contains(&name.as_str()) {
+                sockets.0.insert(
+                    name.to_string(),
+                    offset(*parent, node, &transforms, &parents),
+                );
+            }
+        }
+        commands.entity(*parent).insert(sockets);
+    }
+}
+
+/// Position of `node` relative to `root`, built from local transforms since
+/// freshly spawned scenes have not had their global transforms updated yet
+fn offset(
+    root: Entity,
+    node: Entity,
+    transforms: &Query<&Transform>,
+    parents: &Query<&Parent>,
+) -> Vec3 {
+    let mut point = Vec3::ZERO;
+    let mut current = node;
+    while current != root {
+        if let Ok(transform) = transforms.get(current) {
+            point = transform.transform_point(point);
+        }
+        let Ok(parent) = parents.get(current) else {
+            break;
+        };
+        current = parent.get();
+    }
+    point
+}
@@ -0,0 +1,156 @@
+use core::f32;
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    attachment::AttachmentBonus,
+    definition::BlasterStats,
+    inventory::WeaponSwitch,
+    shot_stats,
+    socket::{socket, ModelSockets, Sockets},
+    CurrentBlaster,
+};
+use crate::player::{Player, PlayerAction, PlayerCam, BASE_FOV};
+
+mod motion;
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins(motion::plugin).add_systems(
+        Update,
+        (
+            setup_view_model,
+            aim,
+            motion::animate,
+            (position_view_model, hold_by_grip, zoom_camera),
+        )
+            .chain(),
+    );
+}
+
+/// Where the held blaster sits relative to the camera
+const HIP_OFFSET: Vec3 = Vec3::new(0., -0.5, -1.);
+/// Where the held blaster sits while aiming, centred just below the camera
+const AIM_OFFSET: Vec3 = Vec3::new(0., -0.15, -0.6);
+/// How far in front of the camera the sight sits while aiming
+const AIM_DISTANCE: f32 = 0.6;
+/// How fast aiming blends in and out, in full blends per second
+const AIM_SPEED: f32 = 6.;
+
+/// Parent of the held blasters, moves them as a whole
+///
+/// Sits under the [`PlayerCam`] so the blasters follow it looking up and down
+#[derive(Component)]
+pub struct ViewModel;
+
+/// How far the player is into aiming down sights, from 0 at the hip to 1
+#[derive(Component, Default)]
+pub struct Aim(pub f32);
+
+fn setup_view_model(
+    mut commands: Commands,
+    cameras: Query<(Entity, &Parent), Added<PlayerCam>>,
+    players: Query<(), With<Player>>,
+) {
+    for (camera, player) in &cameras {
+        if !players.contains(player.get()) {
+            continue;
+        }
+        commands
+            .spawn((
+                Name::new("View Model"),
+                SpatialBundle::from_transform(Transform::from_translation(HIP_OFFSET)),
+                ViewModel,
+                motion::Motion::default(),
+            ))
+            .set_parent(camera);
+        commands.entity(player.get()).insert(Aim::default());
+    }
+}
+
+fn aim(
+    mut player: Query<(
+        &ActionState<PlayerAction>,
+        &mut Aim,
+        Has<CurrentBlaster>,
+        Has<WeaponSwitch>,
+    )>,
+    time: Res<Time>,
+) {
+    for (actions, mut aim, holding, switching) in &mut player {
+        let target = if holding && !switching && actions.pressed(&PlayerAction::Aim) {
+            1.
+        } else {
+            0.
+        };
+        let step = AIM_SPEED * time.delta_seconds();
+        aim.0 += (target - aim.0).clamp(-step, step);
+    }
+}
+
+/// Rotation of a blaster in the view model before recoil or reloading
+fn held_rotation() -> Quat {
+    Quat::from_rotation_y(f32::consts::PI)
+}
+
+fn position_view_model(
+    player: Query<(&Aim, Option<&WeaponSwitch>, Option<&CurrentBlaster>), With<Player>>,
+    blasters: Query<(Option<&ModelSockets>, Option<&Sockets>)>,
+    mut holder: Query<(&mut Transform, &motion::Motion), With<ViewModel>>,
+) {
+    let Ok((aim, switch, current)) = player.get_single() else {
+        return;
+    };
+    let Ok((mut holder, motion)) = holder.get_single_mut() else {
+        return;
+    };
+    // line the sight up with the camera when the blaster has one
+    let aim_offset = current
+        .and_then(|current| blasters.get(current.0).ok())
+        .and_then(|(model, sockets)| {
+            let sight = socket(model, sockets, "sight")?;
+            let grip = socket(model, sockets, "grip").unwrap_or_default();
+            Some(Vec3::NEG_Z * AIM_DISTANCE - held_rotation() * (sight - grip))
+        })
+        .unwrap_or(AIM_OFFSET);
+    holder.translation = HIP_OFFSET.lerp(aim_offset, aim.0)
+        + Vec3::NEG_Y * switch.map_or(0., WeaponSwitch::dip)
+        + motion.offset;
+    // the blaster's own rotation is left for recoil and reloading
+    holder.rotation = motion.rotation;
+}
+
+/// Moves the held blaster so it turns around its grip
+fn hold_by_grip(
+    player: Query<&CurrentBlaster>,
+    mut blasters: Query<(&mut Transform, Option<&ModelSockets>, Option<&Sockets>)>,
+) {
+    for current in &player {
+        let Ok((mut transform, model, sockets)) = blasters.get_mut(current.0) else {
+            continue;
+        };
+        let grip = socket(model, sockets, "grip").unwrap_or_default();
+        transform.translation = -(transform.rotation * grip);
+    }
+}
+
+fn zoom_camera(
+    player: Query<(&Aim, Option<&CurrentBlaster>)>,
+    blasters: Query<(Option<&BlasterStats>, Option<&AttachmentBonus>)>,
+    mut camera: Query<&mut Projection, With<PlayerCam>>,
+) {
+    let Ok((aim, current)) = player.get_single() else {
+        return;
+    };
+    let Ok(mut projection) = camera.get_single_mut() else {
+        return;
+    };
+    let zoom = current
+        .and_then(|current| blasters.get(current.0).ok())
+        .map_or(BlasterStats::default().zoom, |(stats, bonus)| {
+            shot_stats(stats, bonus).zoom
+        });
+    if let Projection::Perspective(perspective) = projection.as_mut() {
+        perspective.fov = BASE_FOV / 1f32.lerp(zoom, aim.0);
+    }
+}
@@ -0,0 +1,187 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use super::Aim;
+use crate::player::{Grounded, Player, PlayerAction};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<ViewModelMotion>()
+        .init_resource::<ViewModelMotion>();
+}
+
+/// Tuning for the procedural view model motion, set a part to `None` to turn it off
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ViewModelMotion {
+    pub sway: Option<Sway>,
+    pub bob: Option<Bob>,
+    pub landing: Option<Landing>,
+    /// How much of the motion is left while fully aimed in
+    pub aim_scale: f32,
+}
+
+impl Default for ViewModelMotion {
+    fn default() -> Self {
+        ViewModelMotion {
+            sway: Some(Sway::default()),
+            bob: Some(Bob::default()),
+            landing: Some(Landing::default()),
+            aim_scale: 0.2,
+        }
+    }
+}
+
+/// Turns the blaster against mouse look so it lags behind the view
+#[derive(Reflect)]
+pub struct Sway {
+    /// Radians per unit of look input
+    pub amount: f32,
+    /// Most the blaster can turn, in radians
+    pub max: f32,
+    /// How fast the blaster catches up, higher is stiffer
+    pub speed: f32,
+}
+
+impl Default for Sway {
+    fn default() -> Self {
+        Sway {
+            amount: 0.01,
+            max: 0.1,
+            speed: 8.,
+        }
+    }
+}
+
+/// Bobs the blaster while walking on the ground
+#[derive(Reflect)]
+pub struct Bob {
+    /// Side to side and up and down distance at full speed
+    pub amount: Vec2,
+    /// Radians of bob per meter walked
+    pub frequency: f32,
+    /// Speed the bob reaches its full amount at, in meters per second
+    pub full_speed: f32,
+}
+
+impl Default for Bob {
+    fn default() -> Self {
+        Bob {
+            amount: Vec2::new(0.02, 0.015),
+            frequency: 2.,
+            full_speed: 8.,
+        }
+    }
+}
+
+/// Drops the blaster when landing from a fall
+#[derive(Reflect)]
+pub struct Landing {
+    /// Distance dropped per meter per second of fall speed
+    pub amount: f32,
+    /// Furthest the blaster can drop
+    pub max: f32,
+    /// How fast the drop recovers, higher is quicker
+    pub recovery: f32,
+}
+
+impl Default for Landing {
+    fn default() -> Self {
+        Landing {
+            amount: 0.01,
+            max: 0.15,
+            recovery: 8.,
+        }
+    }
+}
+
+/// The motion of the view model this frame, added on top of where it is held
+#[derive(Component, Default)]
+pub struct Motion {
+    pub offset: Vec3,
+    pub rotation: Quat,
+    sway: Vec2,
+    bob_phase: f32,
+    bob_weight: f32,
+    dip: f32,
+    /// Fastest the player was falling since last on the ground
+    fall: f32,
+}
+
+pub fn animate(
+    player: Query<
+        (
+            &ActionState<PlayerAction>,
+            &LinearVelocity,
+            &Aim,
+            Has<Grounded>,
+        ),
+        With<Player>,
+    >,
+    mut view_model: Query<&mut Motion>,
+    settings: Res<ViewModelMotion>,
+    time: Res<Time>,
+) {
+    let Ok((actions, velocity, aim, grounded)) = player.get_single() else {
+        return;
+    };
+    let Ok(mut motion) = view_model.get_single_mut() else {
+        return;
+    };
+    let delta = time.delta_seconds();
+    let scale = 1f32.lerp(settings.aim_scale, aim.0);
+
+    motion.sway = match &settings.sway {
+        Some(sway) => {
+            let look = actions.axis_pair(&PlayerAction::Look);
+            let target = (look * sway.amount).clamp_length_max(sway.max);
+            motion.sway.lerp(target, (sway.speed * delta).min(1.))
+        }
+        None => Vec2::ZERO,
+    };
+
+    let mut bob = Vec3::ZERO;
+    if let Some(settings) = &settings.bob {
+        let speed = velocity.0.xz().length();
+        let target = if grounded {
+            (speed / settings.full_speed).min(1.)
+        } else {
+            0.
+        };
+        motion.bob_weight = motion.bob_weight.lerp(target, (10. * delta).min(1.));
+        motion.bob_phase =
+            (motion.bob_phase + speed * settings.frequency * delta) % core::f32::consts::TAU;
+        bob = Vec3::new(
+            motion.bob_phase.sin() * settings.amount.x,
+            (motion.bob_phase * 2.).sin() * settings.amount.y,
+            0.,
+        ) * motion.bob_weight;
+    }
+
+    match &settings.landing {
+        Some(landing) => {
+            if grounded {
+                if motion.fall > 0. {
+                    motion.dip = (motion.dip + motion.fall * landing.amount).min(landing.max);
+                    motion.fall = 0.;
+                }
+            } else {
+                motion.fall = motion.fall.max(-velocity.y);
+            }
+            motion.dip *= (1. - landing.recovery * delta).max(0.);
+        }
+        None => {
+            motion.dip = 0.;
+            motion.fall = 0.;
+        }
+    }
+
+    motion.offset = (bob + Vec3::NEG_Y * motion.dip) * scale;
+    // the view turns against the look input so the blaster turns with it to lag behind
+    motion.rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        motion.sway.x * scale,
+        motion.sway.y * scale,
+        0.,
+    );
+}
@@ -0,0 +1,241 @@
+use avian3d::prelude::*;
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    blaster::{sound_set, BlasterEvent},
+    health::{DamageEvent, DamageKind, Death},
+    map::Despawn,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<Explosion>()
+        .register_type::<Explosive>()
+        .register_type::<ExplosiveShot>()
+        .init_resource::<ExplosionAssets>()
+        .add_systems(Update, (detonate_props, explosive_shots, explode).chain());
+}
+
+/// How long the flash of an explosion lingers
+const FLASH_LIFE: f32 = 0.3;
+
+/// The size and strength of an explosion
+#[derive(Reflect, Clone, Copy, serde::Deserialize)]
+pub struct Blast {
+    pub radius: f32,
+    /// Damage dealt at the center, falling off to nothing at the edge
+    pub damage: f32,
+    /// Impulse given to bodies at the center, falling off like the damage
+    pub force: f32,
+}
+
+impl Blast {
+    /// How much of the blast reaches something `distance` from the center
+    pub fn falloff(&self, distance: f32) -> f32 {
+        (1. - distance / self.radius.max(f32::EPSILON)).clamp(0., 1.)
+    }
+}
+
+#[test]
+fn blast_falls_off_to_the_edge() {
+    let blast = Blast {
+        radius: 4.,
+        damage: 10.,
+        force: 1.,
+    };
+    assert_eq!(blast.falloff(0.), 1.);
+    assert_eq!(blast.falloff(2.), 0.5);
+    assert_eq!(blast.falloff(6.), 0.);
+}
+
+/// A prop that explodes once it dies
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct Explosive(Blast);
+
+/// Makes a blaster's shots explode wherever they hit
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct ExplosiveShot(Blast);
+
+/// A blast going off at `origin`, `source` and its `weapon` are credited with the damage
+#[derive(Event)]
+pub struct Explosion {
+    pub origin: Vec3,
+    pub blast: Blast,
+    pub source: Option<Entity>,
+    pub weapon: Option<Entity>,
+}
+
+#[derive(Resource)]
+struct ExplosionAssets {
+    flash_mesh: Handle<Mesh>,
+    flash_material: Handle<StandardMaterial>,
+    crunch: Vec<Handle<AudioSource>>,
+    rumble: Vec<Handle<AudioSource>>,
+}
+
+impl FromWorld for ExplosionAssets {
+    fn from_world(world: &mut World) -> Self {
+        let server = world.resource::<AssetServer>();
+        let crunch = sound_set("explosionCrunch", 5)
+            .map(|path| server.load(path))
+            .collect();
+        let rumble = sound_set("lowFrequency_explosion", 2)
+            .map(|path| server.load(path))
+            .collect();
+        let flash_mesh = world.resource_mut::<Assets<Mesh>>().add(Sphere::new(1.));
+        let flash_material =
+            world
+                .resource_mut::<Assets<StandardMaterial>>()
+                .add(StandardMaterial {
+                    base_color: Color::srgba(1., 0.5, 0.1, 0.6),
+                    emissive: LinearRgba::rgb(8., 3., 0.5),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..Default::default()
+                });
+        ExplosionAssets {
+            flash_mesh,
+            flash_material,
+            crunch,
+            rumble,
+        }
+    }
+}
+
+fn detonate_props(
+    mut deaths: EventReader<Death>,
+    props: Query<(&Explosive, &GlobalTransform)>,
+    mut explosions: EventWriter<Explosion>,
+) {
+    for Death {
+        target,
+        source,
+        weapon,
+    } in deaths.read()
+    {
+        let Ok((explosive, pos)) = props.get(*target) else {
+            continue;
+        };
+        // whoever set off the first prop is credited with the whole chain
+        explosions.send(Explosion {
+            origin: pos.translation(),
+            blast: explosive.0,
+            source: *source,
+            weapon: *weapon,
+        });
+    }
+}
+
+fn explosive_shots(
+    mut blaster_event: EventReader<BlasterEvent>,
+    blasters: Query<&ExplosiveShot>,
+    mut explosions: EventWriter<Explosion>,
+) {
+    for event in blaster_event.read() {
+        let BlasterEvent::Hit {
+            shooter,
+            blaster,
+            point,
+            ..
+        } = event
+        else {
+            continue;
+        };
+        let Ok(shot) = blasters.get(*blaster) else {
+            continue;
+        };
+        explosions.send(Explosion {
+            origin: *point,
+            blast: shot.0,
+            source: Some(*shooter),
+            weapon: Some(*blaster),
+        });
+    }
+}
+
+fn explode(
+    mut commands: Commands,
+    mut explosions: EventReader<Explosion>,
+    spatial_query: SpatialQuery,
+    colliders: Query<&ColliderParent>,
+    mut bodies: Query<(&GlobalTransform, Option<(&RigidBody, &mut ExternalImpulse)>)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    assets: Res<ExplosionAssets>,
+) {
+    use rand::seq::*;
+    let mut rng = rand::thread_rng();
+    for Explosion {
+        origin,
+        blast,
+        source,
+        weapon,
+    } in explosions.read()
+    {
+        let hits = spatial_query.shape_intersections(
+            &Collider::sphere(blast.radius),
+            *origin,
+            Quat::IDENTITY,
+            SpatialQueryFilter::default(),
+        );
+        // a body with several colliders is only caught once
+        let mut caught = Vec::new();
+        for collider in hits {
+            let body = colliders
+                .get(collider)
+                .map_or(collider, ColliderParent::get);
+            if caught.contains(&body) {
+                continue;
+            }
+            caught.push(body);
+            let Ok((pos, impulse)) = bodies.get_mut(body) else {
+                continue;
+            };
+            let offset = pos.translation() - *origin;
+            let falloff = blast.falloff(offset.length());
+            if falloff > 0. {
+                damage_events.send(DamageEvent {
+                    target: body,
+                    source: *source,
+                    weapon: *weapon,
+                    amount: blast.damage * falloff,
+                    kind: DamageKind::Explosive,
+                });
+            }
+            if let Some((RigidBody::Dynamic, mut impulse)) = impulse {
+                let direction = Dir3::new(offset).unwrap_or(Dir3::Y);
+                impulse.apply_impulse(*direction * blast.force * falloff);
+            }
+        }
+        // the flash shrinks away with its parent, sized to the blast by the child
+        commands
+            .spawn((
+                Name::new("Explosion Flash"),
+                SpatialBundle::from_transform(Transform::from_translation(*origin)),
+                Despawn::new(FLASH_LIFE),
+            ))
+            .with_children(|flash| {
+                flash.spawn(PbrBundle {
+                    mesh: assets.flash_mesh.clone(),
+                    material: assets.flash_material.clone(),
+                    transform: Transform::from_scale(Vec3::splat(blast.radius * 0.5)),
+                    ..Default::default()
+                });
+            });
+        for (sounds, volume) in [(&assets.crunch, 1.), (&assets.rumble, 0.8)] {
+            let Some(source) = sounds.choose(&mut rng) else {
+                continue;
+            };
+            commands.spawn((
+                Name::new("Explosion Sound"),
+                SpatialBundle::from_transform(Transform::from_translation(*origin)),
+                AudioBundle {
+                    source: source.clone(),
+                    settings: PlaybackSettings::DESPAWN
+                        .with_spatial(true)
+                        .with_volume(Volume::new(volume)),
+                },
+            ));
+        }
+    }
+}
@@ -1,17 +1,168 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use rand::Rng;
 
 use crate::map::{Cell, MapCellBundle};
 
+mod fracture;
+mod shield;
+
+pub use shield::{Armor, Shield};
+
 pub fn plugin(app: &mut App) {
-    app.register_type::<Health>()
+    app.add_event::<DamageEvent>()
+        .add_event::<Death>()
+        .register_type::<Health>()
+        .register_type::<Resistances>()
         .register_type::<DropTable>()
-        .add_systems(Update, (set_drops, spawn_drops));
+        // damage lands before Update so everything reacting to a Death sees the target
+        // before fracture removes it in PostUpdate
+        .add_systems(PreUpdate, apply_damage)
+        .add_systems(Update, (set_drops, spawn_drops))
+        .add_plugins((fracture::plugin, shield::plugin));
+}
+
+/// How much damage something can take before it is [`Dead`]
+///
+/// Cells only give the max, everything starts out at full health
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+#[serde(from = "MaxHealth")]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+#[derive(serde::Deserialize)]
+struct MaxHealth(f32);
+
+impl From<MaxHealth> for Health {
+    fn from(MaxHealth(max): MaxHealth) -> Self {
+        Health::new(max)
+    }
 }
 
+impl Health {
+    pub fn new(max: f32) -> Health {
+        Health { current: max, max }
+    }
+}
+
+/// Marks something whose [`Health`] ran out, it stays queryable until something removes it
+#[derive(Component)]
+pub struct Dead;
+
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Deserialize)]
+pub enum DamageKind {
+    #[default]
+    Kinetic,
+    Energy,
+    Explosive,
+}
+
+/// Share of each [`DamageKind`] that is blocked, negative values make the target weak to it
 #[derive(Component, Reflect, serde::Deserialize)]
 #[reflect(Deserialize, Component)]
-pub struct Health(pub u8);
+pub struct Resistances(HashMap<DamageKind, f32>);
+
+impl Resistances {
+    pub fn scale(&self, kind: DamageKind, amount: f32) -> f32 {
+        let resist = self.0.get(&kind).copied().unwrap_or(0.);
+        amount * (1. - resist).max(0.)
+    }
+}
+
+#[test]
+fn resistances_scale_damage() {
+    let resistances = Resistances(HashMap::from_iter([
+        (DamageKind::Explosive, 0.75),
+        (DamageKind::Energy, -0.5),
+    ]));
+    assert_eq!(resistances.scale(DamageKind::Explosive, 8.), 2.);
+    assert_eq!(resistances.scale(DamageKind::Energy, 2.), 3.);
+    assert_eq!(resistances.scale(DamageKind::Kinetic, 2.), 2.);
+}
+
+/// Asks for `amount` damage to be dealt to `target`, `source` is whoever is responsible
+///
+/// `weapon` is the blaster it came from, carried along since the shooter may have dropped it by now
+#[derive(Event, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub weapon: Option<Entity>,
+    pub amount: f32,
+    pub kind: DamageKind,
+}
+
+/// `target` ran out of [`Health`], `source` dealt the last of the damage with `weapon`
+#[derive(Event, Clone, Copy)]
+pub struct Death {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub weapon: Option<Entity>,
+}
+
+/// A collider that scales the damage dealt through it, declared in a cell's `hitboxes`
+#[derive(Component, Clone)]
+pub struct Hitbox {
+    /// Reported with hits so things like a target can score its bullseye
+    pub zone: String,
+    pub multiplier: f32,
+}
+
+impl Hitbox {
+    pub fn scale(&self, amount: f32) -> f32 {
+        amount * self.multiplier
+    }
+}
+
+/// The only place [`Health`] is taken away, so resistances, [`Armor`] and [`Shield`]s apply to everything
+fn apply_damage(
+    mut commands: Commands,
+    mut damage: EventReader<DamageEvent>,
+    mut targets: Query<
+        (
+            &mut Health,
+            Option<&Resistances>,
+            Option<&Armor>,
+            Option<&mut Shield>,
+        ),
+        Without<Dead>,
+    >,
+    mut deaths: EventWriter<Death>,
+) {
+    for DamageEvent {
+        target,
+        source,
+        weapon,
+        amount,
+        kind,
+    } in damage.read()
+    {
+        let Ok((mut health, resistances, armor, shield)) = targets.get_mut(*target) else {
+            continue;
+        };
+        // already killed by an earlier event this frame
+        if health.current <= 0. {
+            continue;
+        }
+        let amount = resistances.map_or(*amount, |resistances| resistances.scale(*kind, *amount));
+        let amount = armor.map_or(amount, |armor| armor.scale(amount));
+        let amount = match shield {
+            Some(mut shield) => shield.absorb(amount),
+            None => amount,
+        };
+        health.current = (health.current - amount).max(0.);
+        if health.current <= 0. {
+            commands.entity(*target).insert(Dead);
+            deaths.send(Death {
+                target: *target,
+                source: *source,
+                weapon: *weapon,
+            });
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct Drops(Vec<(Vec3, Handle<Cell>)>);
@@ -79,12 +230,11 @@ fn set_drops(
 
 fn spawn_drops(
     mut commands: Commands,
-    mut dead: RemovedComponents<Health>,
-    drops: Query<(Entity, &Drops, &Transform)>,
+    mut deaths: EventReader<Death>,
+    drops: Query<(&Drops, &Transform)>,
 ) {
-    for died in dead.read() {
-        if let Ok((target, drops, pos)) = drops.get(died) {
-            commands.entity(target).despawn_recursive();
+    for death in deaths.read() {
+        if let Ok((drops, pos)) = drops.get(death.target) {
             for drop in drops.0.iter() {
                 let mut pos = *pos;
                 pos.translation += drop.0;
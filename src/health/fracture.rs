@@ -0,0 +1,176 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::Death;
+use crate::map::{Cell, Despawn, MapCellBundle};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Fracture>()
+        .init_resource::<DebrisMaterial>()
+        .add_systems(PostUpdate, remove_dead_props);
+}
+
+/// How long a dead prop without a [`Fracture`] takes to shrink away
+const DEAD_PROP_LIFE: f32 = 1.;
+/// Most a split piece is thrown out from the prop's center, in meters per second
+const SCATTER_SPEED: f32 = 1.5;
+
+/// What a prop breaks into when it dies, the pieces take its place and shrink away over `life` seconds
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub enum Fracture {
+    /// Spawns each cell at its offset from the prop
+    Debris {
+        cells: Vec<(String, Vec3)>,
+        life: f32,
+    },
+    /// Cuts the prop's cuboid collider into `pieces` along each axis
+    Split { pieces: u32, life: f32 },
+}
+
+#[derive(Resource)]
+struct DebrisMaterial(Handle<StandardMaterial>);
+
+impl FromWorld for DebrisMaterial {
+    fn from_world(world: &mut World) -> Self {
+        DebrisMaterial(
+            world
+                .resource_mut::<Assets<StandardMaterial>>()
+                .add(StandardMaterial {
+                    base_color: Color::srgb(0.55, 0.5, 0.45),
+                    perceptual_roughness: 0.9,
+                    ..Default::default()
+                }),
+        )
+    }
+}
+
+/// Centers and size of `pieces` cubes along each axis filling a cuboid with `half_extents`
+fn split(half_extents: Vec3, pieces: u32) -> (Vec<Vec3>, Vec3) {
+    let pieces = pieces.max(1);
+    let size = half_extents * 2. / pieces as f32;
+    let mut centers = Vec::new();
+    for x in 0..pieces {
+        for y in 0..pieces {
+            for z in 0..pieces {
+                let cell = Vec3::new(x as f32, y as f32, z as f32) + 0.5;
+                centers.push(cell * size - half_extents);
+            }
+        }
+    }
+    (centers, size)
+}
+
+#[test]
+fn split_fills_the_cuboid() {
+    let (centers, size) = split(Vec3::new(1., 0.5, 1.), 2);
+    assert_eq!(centers.len(), 8);
+    assert_eq!(size, Vec3::new(1., 0.5, 1.));
+    assert!(centers.contains(&Vec3::new(-0.5, -0.25, -0.5)));
+    assert!(centers.contains(&Vec3::new(0.5, 0.25, 0.5)));
+}
+
+/// Every prop that dies is removed here, after everything reacting to its [`Death`] has seen it
+fn remove_dead_props(
+    mut commands: Commands,
+    mut deaths: EventReader<Death>,
+    props: Query<
+        (
+            &GlobalTransform,
+            Option<&LinearVelocity>,
+            Option<&Fracture>,
+            Option<&Children>,
+        ),
+        With<Handle<Cell>>,
+    >,
+    colliders: Query<(&Collider, &Transform)>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    material: Res<DebrisMaterial>,
+) {
+    let mut rng = rand::thread_rng();
+    for Death { target, .. } in deaths.read() {
+        let Ok((pos, velocity, fracture, children)) = props.get(*target) else {
+            continue;
+        };
+        let Some(fracture) = fracture else {
+            commands
+                .entity(*target)
+                .insert(Despawn::new(DEAD_PROP_LIFE));
+            continue;
+        };
+        let pos = pos.compute_transform();
+        let velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.0);
+        match fracture {
+            Fracture::Debris { cells, life } => {
+                for (cell, offset) in cells {
+                    commands.spawn((
+                        MapCellBundle {
+                            transform: pos * Transform::from_translation(*offset),
+                            cell: asset_server.load(cell),
+                            ..Default::default()
+                        },
+                        LinearVelocity(velocity),
+                        Despawn::new(*life),
+                    ));
+                }
+            }
+            Fracture::Split { pieces, life } => {
+                // the collider is either on the prop or on a child offset from it
+                let Some((collider, offset)) = std::iter::once(*target)
+                    .chain(children.into_iter().flatten().copied())
+                    .find_map(|entity| {
+                        let (collider, transform) = colliders.get(entity).ok()?;
+                        let offset = if entity == *target {
+                            Vec3::ZERO
+                        } else {
+                            transform.translation
+                        };
+                        Some((collider, offset))
+                    })
+                else {
+                    commands
+                        .entity(*target)
+                        .insert(Despawn::new(DEAD_PROP_LIFE));
+                    continue;
+                };
+                let Some(cuboid) = collider.shape_scaled().as_cuboid() else {
+                    error!("Split fracture needs a cuboid collider");
+                    commands
+                        .entity(*target)
+                        .insert(Despawn::new(DEAD_PROP_LIFE));
+                    continue;
+                };
+                let (centers, size) = split(cuboid.half_extents.into(), *pieces);
+                // the pieces are sized by their mesh since Despawn takes over their scale
+                let mesh = meshes.add(Cuboid::from_size(size));
+                for center in centers {
+                    let scatter = center.normalize_or_zero() * rng.gen_range(0. ..SCATTER_SPEED);
+                    commands.spawn((
+                        Name::new("Debris"),
+                        PbrBundle {
+                            mesh: mesh.clone(),
+                            material: material.0.clone(),
+                            transform: Transform::from_translation(
+                                pos.transform_point(offset + center),
+                            )
+                            .with_rotation(pos.rotation),
+                            ..Default::default()
+                        },
+                        RigidBody::Dynamic,
+                        Collider::cuboid(size.x, size.y, size.z),
+                        LinearVelocity(velocity + pos.rotation * scatter),
+                        AngularVelocity(Vec3::new(
+                            rng.gen_range(-2. ..2.),
+                            rng.gen_range(-2. ..2.),
+                            rng.gen_range(-2. ..2.),
+                        )),
+                        Despawn::new(*life),
+                    ));
+                }
+            }
+        }
+        commands.entity(*target).despawn_recursive();
+    }
+}
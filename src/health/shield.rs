@@ -0,0 +1,170 @@
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{blaster::sound_set, interact::InteractEvent, player::Player};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Shield>()
+        .register_type::<Armor>()
+        .register_type::<ArmorPickup>()
+        .init_resource::<ShieldSounds>()
+        .add_systems(Update, (recharge_shields, pickup_armor));
+}
+
+/// Soaks up damage before [`super::Health`] and regenerates once nothing has hit it for `delay` seconds
+///
+/// Cells give the settings, shields start out full
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+#[serde(from = "ShieldSettings")]
+pub struct Shield {
+    max: f32,
+    delay: f32,
+    /// Shield regained per second while recharging
+    rate: f32,
+    current: f32,
+    since_hit: f32,
+    broken: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct ShieldSettings {
+    max: f32,
+    delay: f32,
+    rate: f32,
+}
+
+impl From<ShieldSettings> for Shield {
+    fn from(ShieldSettings { max, delay, rate }: ShieldSettings) -> Self {
+        Shield::new(max, delay, rate)
+    }
+}
+
+impl Shield {
+    pub fn new(max: f32, delay: f32, rate: f32) -> Shield {
+        Shield {
+            max,
+            delay,
+            rate,
+            current: max,
+            since_hit: delay,
+            broken: false,
+        }
+    }
+
+    /// Takes what it can of `amount`, returning the damage that gets through
+    pub fn absorb(&mut self, amount: f32) -> f32 {
+        self.since_hit = 0.;
+        let absorbed = amount.min(self.current);
+        self.current -= absorbed;
+        amount - absorbed
+    }
+
+    pub fn refill(&mut self) {
+        self.current = self.max;
+        self.since_hit = self.delay;
+        self.broken = false;
+    }
+}
+
+#[test]
+fn shield_absorbs_before_health() {
+    let mut shield = Shield::new(5., 2., 1.);
+    assert_eq!(shield.absorb(3.), 0.);
+    assert_eq!(shield.absorb(3.), 1.);
+    assert_eq!(shield.absorb(3.), 3.);
+}
+
+/// Share of all incoming damage that is ignored
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct Armor(f32);
+
+impl Armor {
+    pub fn scale(&self, amount: f32) -> f32 {
+        amount * (1. - self.0.clamp(0., 1.))
+    }
+}
+
+/// Gives the player [`Armor`] blocking this share of damage, keeping whichever is better
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct ArmorPickup(f32);
+
+#[derive(Resource)]
+struct ShieldSounds(Vec<Handle<AudioSource>>);
+
+impl FromWorld for ShieldSounds {
+    fn from_world(world: &mut World) -> Self {
+        let server = world.resource::<AssetServer>();
+        ShieldSounds(
+            sound_set("forceField", 5)
+                .map(|path| server.load(path))
+                .collect(),
+        )
+    }
+}
+
+fn recharge_shields(
+    mut commands: Commands,
+    mut shields: Query<(Entity, &mut Shield)>,
+    sounds: Res<ShieldSounds>,
+    time: Res<Time>,
+) {
+    use rand::seq::*;
+    for (entity, mut shield) in &mut shields {
+        shield.since_hit += time.delta_seconds();
+        // breaking plays low and recharging plays high
+        let speed = if !shield.broken && shield.current <= 0. {
+            shield.broken = true;
+            0.7
+        } else if shield.since_hit < shield.delay || shield.current >= shield.max {
+            continue;
+        } else {
+            shield.current = (shield.current + shield.rate * time.delta_seconds()).min(shield.max);
+            if !shield.broken {
+                continue;
+            }
+            shield.broken = false;
+            1.2
+        };
+        let Some(source) = sounds.0.choose(&mut rand::thread_rng()) else {
+            continue;
+        };
+        commands
+            .spawn((
+                Name::new("Shield Sound"),
+                SpatialBundle::default(),
+                AudioBundle {
+                    source: source.clone(),
+                    settings: PlaybackSettings::DESPAWN
+                        .with_spatial(true)
+                        .with_speed(speed)
+                        .with_volume(Volume::new(0.7)),
+                },
+            ))
+            .set_parent(entity);
+    }
+}
+
+fn pickup_armor(
+    mut commands: Commands,
+    mut interactions: EventReader<InteractEvent>,
+    pickups: Query<&ArmorPickup>,
+    mut players: Query<Option<&mut Armor>, With<Player>>,
+) {
+    for InteractEvent { player, target } in interactions.read() {
+        let Ok(pickup) = pickups.get(*target) else {
+            continue;
+        };
+        let Ok(armor) = players.get_mut(*player) else {
+            continue;
+        };
+        match armor {
+            Some(mut armor) => armor.0 = armor.0.max(pickup.0),
+            None => {
+                commands.entity(*player).insert(Armor(pickup.0));
+            }
+        }
+        commands.entity(*target).despawn_recursive();
+    }
+}
@@ -0,0 +1,151 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::player::{Player, PlayerAction, PlayerCam};
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<InteractEvent>()
+        .register_type::<Interactable>()
+        .add_systems(Startup, spawn_prompt)
+        .add_systems(
+            Update,
+            (setup_focus, find_focus, interact, update_prompt).chain(),
+        );
+}
+
+/// How far away the player can interact with things
+const REACH: f32 = 2.5;
+
+/// Something the player can interact with, shown in the prompt as the given name
+///
+/// What the interaction does is up to the systems reading [`InteractEvent`]
+#[derive(Component, Reflect, serde::Deserialize)]
+#[reflect(Deserialize, Component)]
+pub struct Interactable(pub String);
+
+/// `player` interacted with `target`, the entity holding the [`Interactable`]
+#[derive(Event)]
+pub struct InteractEvent {
+    pub player: Entity,
+    pub target: Entity,
+}
+
+/// The [`Interactable`] the player is looking at
+#[derive(Component, Default)]
+pub struct Focus(pub Option<Entity>);
+
+#[derive(Component)]
+struct InteractPrompt;
+
+fn spawn_prompt(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Interact Prompt"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    bottom: Val::Percent(35.),
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ))
+        .with_children(|prompt| {
+            prompt.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.,
+                        ..Default::default()
+                    },
+                ),
+                InteractPrompt,
+            ));
+        });
+}
+
+fn setup_focus(mut commands: Commands, players: Query<Entity, Added<Player>>) {
+    for player in &players {
+        commands.entity(player).insert(Focus::default());
+    }
+}
+
+fn find_focus(
+    mut player: Query<&mut Focus, With<Player>>,
+    camera: Query<&RayHits, With<PlayerCam>>,
+    interactables: Query<(), With<Interactable>>,
+    parents: Query<&Parent>,
+    colliders: Query<&ColliderParent>,
+    bodies: Query<&RigidBody>,
+) {
+    let Ok(mut focus) = player.get_single_mut() else {
+        return;
+    };
+    let Ok(hits) = camera.get_single() else {
+        return;
+    };
+    let mut found = None;
+    for hit in hits.iter_sorted() {
+        if hit.time_of_impact > REACH {
+            break;
+        }
+        // colliders are either on the interactable or one of its children
+        let target = if interactables.contains(hit.entity) {
+            Some(hit.entity)
+        } else {
+            parents
+                .get(hit.entity)
+                .ok()
+                .map(Parent::get)
+                .filter(|parent| interactables.contains(*parent))
+        };
+        if target.is_some() {
+            found = target;
+            break;
+        }
+        // walls and floors block the reach
+        if colliders
+            .get(hit.entity)
+            .is_ok_and(|body| bodies.get(body.get()).is_ok_and(RigidBody::is_static))
+        {
+            break;
+        }
+    }
+    if focus.0 != found {
+        focus.0 = found;
+    }
+}
+
+fn interact(
+    player: Query<(Entity, &ActionState<PlayerAction>, &Focus), With<Player>>,
+    mut interactions: EventWriter<InteractEvent>,
+) {
+    for (player, actions, focus) in &player {
+        let Some(target) = focus.0 else {
+            continue;
+        };
+        if actions.just_pressed(&PlayerAction::Interact) {
+            interactions.send(InteractEvent { player, target });
+        }
+    }
+}
+
+fn update_prompt(
+    player: Query<&Focus, (With<Player>, Changed<Focus>)>,
+    interactables: Query<&Interactable>,
+    mut prompt: Query<&mut Text, With<InteractPrompt>>,
+) {
+    let Ok(focus) = player.get_single() else {
+        return;
+    };
+    let Ok(mut prompt) = prompt.get_single_mut() else {
+        return;
+    };
+    prompt.sections[0].value = match focus.0.and_then(|target| interactables.get(target).ok()) {
+        Some(Interactable(name)) => format!("[E] {}", name),
+        None => String::new(),
+    };
+}
@@ -11,7 +11,9 @@ use bevy_editor_pls::{egui::widgets, EditorPlugin};
 use leafwing_input_manager::prelude::*;
 
 mod blaster;
+mod explosion;
 mod health;
+mod interact;
 mod map;
 mod player;
 
@@ -19,7 +21,14 @@ fn main() {
     let mut app = App::new();
     app.add_plugins((DefaultPlugins, avian3d::PhysicsPlugins::default()))
         .add_systems(Startup, (spawn_world, test_spawn))
-        .add_plugins((player::plugin, map::plugin, blaster::plugin, health::plugin));
+        .add_plugins((
+            player::plugin,
+            map::plugin,
+            blaster::plugin,
+            health::plugin,
+            interact::plugin,
+            explosion::plugin,
+        ));
     #[cfg(debug_assertions)]
     app.add_plugins((
         EditorPlugin::new(),
@@ -83,4 +92,5 @@ enum Layers {
     Player,
     Ground,
     Blasters,
+    Projectiles,
 }
@@ -3,13 +3,18 @@ use core::f32;
 use avian3d::prelude::*;
 use bevy::{
     asset::{AssetLoader, AsyncReadExt},
+    ecs::system::EntityCommands,
     prelude::*,
     render::render_asset::RenderAssetUsages,
 };
 
+use crate::health::Hitbox;
+
 mod asset_loading;
 mod map_editor;
 
+pub use map_editor::{MapRules, RespawnPlayer};
+
 #[derive(Component)]
 struct MapRoot;
 
@@ -35,6 +40,9 @@ pub struct Cell {
     #[reflect(ignore)]
     components: Vec<Box<dyn Reflect>>,
     layer: Option<(u32, u32)>,
+    mass: f32,
+    #[reflect(ignore)]
+    hitboxes: Vec<(Collider, Vec3, Hitbox)>,
 }
 
 use bitflags::bitflags;
@@ -176,37 +184,67 @@ struct AddDynamicComponents;
 fn update_cell(commands: &mut Commands, target: Entity, asset: &Cell) {
     let mut cell = commands.entity(target);
     cell.despawn_descendants();
+    cell.insert(asset.scene.clone());
+    insert_physics(&mut cell, asset);
+    if !asset.components.is_empty() {
+        cell.insert(AddDynamicComponents);
+    }
+}
+
+/// Collider children spawned by [`insert_physics`], replaced each time it runs
+#[derive(Component)]
+struct CellPhysics;
+
+/// Gives a cell the body and colliders from its asset,
+/// used when something that took them away hands the cell back to physics
+pub fn insert_physics(cell: &mut EntityCommands, asset: &Cell) {
+    // running again replaces the colliders instead of piling up more
+    cell.add(|cell: Entity, world: &mut World| {
+        let old = world
+            .get::<Children>(cell)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|child| world.get::<CellPhysics>(*child).is_some())
+            .collect::<Vec<_>>();
+        for child in old {
+            world.entity_mut(child).despawn_recursive();
+        }
+    });
     cell.remove::<Collider>();
-    cell.insert((asset.scene.clone(), asset.body, Mass(10.)));
+    cell.insert((asset.body, Mass(asset.mass)));
     if let Some(offset) = asset.collider_offset {
         cell.with_children(|p| {
-            let mut c = p.spawn((
+            p.spawn((
                 SpatialBundle {
                     transform: Transform::from_translation(offset),
                     ..Default::default()
                 },
                 asset.collider.clone(),
-                Mass(10.),
+                Mass(asset.mass),
+                CellPhysics,
             ));
-            // if let Some((memberships, filters)) = asset.layer {
-            //     c.insert(CollisionGroups::new(
-            //         Group::from_bits_retain(memberships),
-            //         Group::from_bits_retain(filters),
-            //     ));
-            // }
         });
     } else {
         cell.insert(asset.collider.clone());
-        if let Some((memberships, filters)) = asset.layer {
-            // cell.insert(CollisionGroups::new(
-            //     Group::from_bits_retain(memberships),
-            //     Group::from_bits_retain(filters),
-            // ));
-        }
-    }
-    if !asset.components.is_empty() {
-        cell.insert(AddDynamicComponents);
     }
+    // hitboxes only change where damage lands, so they are sensors that add no mass
+    // and are still found by ray casts
+    cell.with_children(|p| {
+        for (collider, offset, hitbox) in asset.hitboxes.iter() {
+            p.spawn((
+                SpatialBundle {
+                    transform: Transform::from_translation(*offset),
+                    ..Default::default()
+                },
+                collider.clone(),
+                Sensor,
+                ColliderDensity(0.),
+                hitbox.clone(),
+                CellPhysics,
+            ));
+        }
+    });
 }
 
 fn add_dynamic_components(world: &mut World) {
@@ -9,6 +9,7 @@ use bevy::{
 };
 
 use super::{Cell, TileDirection};
+use crate::health::Hitbox;
 
 #[derive(serde::Serialize, serde::Deserialize)]
 enum ColliderAsset {
@@ -69,6 +70,8 @@ fn print_cell() {
             // can_tile: TileDirection::X | TileDirection::Z,
             components: HashMap::default(),
             layer: None,
+            hitboxes: Vec::new(),
+            mass: 10.,
         })
     );
 }
@@ -88,12 +91,30 @@ struct CellAsset {
     components: HashMap<String, String>,
     #[serde(default)]
     layer: Option<(u32, u32)>,
+    #[serde(default)]
+    hitboxes: Vec<HitboxAsset>,
+    #[serde(default = "ten")]
+    mass: f32,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HitboxAsset {
+    collider: ColliderAsset,
+    #[serde(default)]
+    offset: Vec3,
+    zone: String,
+    #[serde(default = "one")]
+    multiplier: f32,
 }
 
 fn one() -> f32 {
     1.
 }
 
+fn ten() -> f32 {
+    10.
+}
+
 fn fixed() -> RigidBody {
     RigidBody::Static
 }
@@ -182,6 +203,21 @@ async fn load_cell_asset<'a>(
         // can_tile: cell.can_tile,
         components,
         layer: cell.layer,
+        mass: cell.mass,
+        hitboxes: cell
+            .hitboxes
+            .into_iter()
+            .map(|hitbox| {
+                (
+                    hitbox.collider.into(),
+                    hitbox.offset,
+                    Hitbox {
+                        zone: hitbox.zone,
+                        multiplier: hitbox.multiplier,
+                    },
+                )
+            })
+            .collect(),
     };
     Ok(cell)
 }
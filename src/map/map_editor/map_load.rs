@@ -1,26 +1,40 @@
+use avian3d::prelude::*;
 use bevy::prelude::*;
 
 use crate::{
+    health::{Armor, Dead, Health, Shield},
     map::{MapCellBundle, MapRoot},
     player::Player,
 };
 
-use super::MapData;
+use super::{MapData, MapRules};
 
 pub fn plugin(app: &mut App) {
     app.init_state::<MapLoadState>()
+        .add_event::<RespawnPlayer>()
         .insert_resource(LoadMap("Maps/menu.map".to_string()))
         .init_resource::<CurrentMap>()
+        .init_resource::<MapRules>()
         .add_systems(
             Update,
             (
                 load_map.run_if(in_state(MapLoadState::Loaded)),
                 wait_for_loading.run_if(in_state(MapLoadState::Loading)),
+                respawn_player,
             ),
         )
         .add_systems(OnEnter(MapLoadState::Spawning), (spawn_map, reset_player));
 }
 
+/// Puts a player back at the map's spawn with full health and no velocity
+#[derive(Event)]
+pub struct RespawnPlayer {
+    pub player: Entity,
+    /// Respawning after a death, when the [`MapRules`] decide what the player keeps,
+    /// rather than arriving on a newly loaded map
+    pub died: bool,
+}
+
 #[derive(Resource, Default)]
 pub struct CurrentMap(pub Handle<MapData>);
 
@@ -58,6 +72,7 @@ fn spawn_map(
         error!("Map should be loaded");
         return;
     };
+    commands.insert_resource(data.rules.clone());
     commands
         .spawn((Name::new("Map Root"), SpatialBundle::default(), MapRoot))
         .with_children(|p| {
@@ -72,17 +87,55 @@ fn spawn_map(
     next.set(MapLoadState::Loaded)
 }
 
-fn reset_player(
-    mut players: Query<&mut Transform, With<Player>>,
+fn reset_player(players: Query<Entity, With<Player>>, mut respawn: EventWriter<RespawnPlayer>) {
+    for player in &players {
+        respawn.send(RespawnPlayer {
+            player,
+            died: false,
+        });
+    }
+}
+
+fn respawn_player(
+    mut commands: Commands,
+    mut respawn: EventReader<RespawnPlayer>,
+    mut players: Query<
+        (
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            Option<&mut Health>,
+            Option<&mut Shield>,
+        ),
+        With<Player>,
+    >,
     current: Res<CurrentMap>,
     map_data: Res<Assets<MapData>>,
+    rules: Res<MapRules>,
 ) {
-    let Some(data) = map_data.get(current.0.id()) else {
-        error!("Map should be loaded");
-        return;
-    };
-    for mut player in &mut players {
-        *player = data.spawn;
+    for RespawnPlayer { player, died } in respawn.read() {
+        let Some(data) = map_data.get(current.0.id()) else {
+            error!("Map should be loaded");
+            return;
+        };
+        let Ok((mut transform, mut velocity, mut spin, health, shield)) = players.get_mut(*player)
+        else {
+            continue;
+        };
+        *transform = data.spawn;
+        velocity.0 = Vec3::ZERO;
+        spin.0 = Vec3::ZERO;
+        if let Some(mut health) = health {
+            health.current = health.max;
+        }
+        if let Some(mut shield) = shield {
+            shield.refill();
+        }
+        commands.entity(*player).remove::<Dead>();
+        // armor picked up is lost on death like the inventory, unless the map keeps it
+        if *died && !rules.keep_armor {
+            commands.entity(*player).remove::<Armor>();
+        }
     }
 }
 
@@ -4,6 +4,8 @@ use map_load::LoadMap;
 mod asset_loading;
 mod map_load;
 
+pub use map_load::RespawnPlayer;
+
 #[derive(serde::Serialize, serde::Deserialize, Reflect)]
 struct Tile {
     cell: String,
@@ -14,6 +16,30 @@ struct Tile {
 struct MapData {
     spawn: Transform,
     tiles: Vec<Tile>,
+    #[serde(default)]
+    rules: MapRules,
+}
+
+/// How the current map treats players, taken from its [`MapData`] when it spawns
+#[derive(Resource, Reflect, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MapRules {
+    /// Seconds a dead player waits before respawning
+    pub respawn_time: f32,
+    /// Whether blasters are kept through respawning after a death
+    pub keep_inventory: bool,
+    /// Whether [`Armor`](crate::health::Armor) is kept through respawning after a death
+    pub keep_armor: bool,
+}
+
+impl Default for MapRules {
+    fn default() -> Self {
+        MapRules {
+            respawn_time: 3.,
+            keep_inventory: false,
+            keep_armor: false,
+        }
+    }
 }
 
 pub fn plugin(app: &mut App) {
@@ -26,6 +52,7 @@ pub fn plugin(app: &mut App) {
 fn print_mapdate() {
     let map = MapData {
         spawn: Transform::IDENTITY,
+        rules: MapRules::default(),
         tiles: vec![
             Tile {
                 cell: String::from("floor.cell"),
@@ -9,6 +9,11 @@ use bevy::{
 use bevy_editor_pls::{egui::widgets, EditorPlugin};
 use leafwing_input_manager::prelude::*;
 
+use crate::{
+    health::{Dead, Death, Health, Shield},
+    map::{MapRules, RespawnPlayer},
+};
+
 pub fn plugin(app: &mut App) {
     app.add_plugins(InputManagerPlugin::<PlayerAction>::default())
         .add_systems(Startup, (spawn_player, lock_mouse))
@@ -19,6 +24,8 @@ pub fn plugin(app: &mut App) {
                 apply_movement_damping,
                 player_move,
                 player_look,
+                player_death,
+                respawn_timer,
                 toggle_mouse.run_if(input_just_pressed(KeyCode::Escape)),
                 noclip.run_if(input_just_pressed(KeyCode::F11)),
             ),
@@ -47,6 +54,15 @@ fn toggle_mouse(mut window: Query<&mut Window, With<PrimaryWindow>>) {
     }
 }
 
+/// Field of view of the [`PlayerCam`] when not zoomed in
+pub const BASE_FOV: f32 = f32::consts::FRAC_PI_4;
+const PLAYER_HEALTH: f32 = 100.;
+const PLAYER_SHIELD: f32 = 50.;
+/// Seconds without being hit before the player's shield recharges
+const PLAYER_SHIELD_DELAY: f32 = 4.;
+/// Shield the player regains per second while recharging
+const PLAYER_SHIELD_RATE: f32 = 15.;
+
 #[derive(Component)]
 pub struct PlayerCam;
 
@@ -64,6 +80,15 @@ pub enum PlayerAction {
     FlyDown,
     Shoot,
     Jump,
+    Reload,
+    NextWeapon,
+    PrevWeapon,
+    Slot1,
+    Slot2,
+    Slot3,
+    Drop,
+    Aim,
+    Interact,
 }
 
 impl Actionlike for PlayerAction {
@@ -82,11 +107,20 @@ fn player_bindings() -> InputMap<PlayerAction> {
         (PlayerAction::MoveLeft, KeyCode::KeyA),
         (PlayerAction::MoveRight, KeyCode::KeyD),
         (PlayerAction::Jump, KeyCode::Space),
+        (PlayerAction::Reload, KeyCode::KeyR),
+        (PlayerAction::Slot1, KeyCode::Digit1),
+        (PlayerAction::Slot2, KeyCode::Digit2),
+        (PlayerAction::Slot3, KeyCode::Digit3),
+        (PlayerAction::Drop, KeyCode::KeyG),
+        (PlayerAction::Interact, KeyCode::KeyE),
     ])
     .with_dual_axis(PlayerAction::Look, MouseMove::default().sensitivity(0.1));
     map.insert(PlayerAction::FlyUp, KeyCode::Space)
         .insert(PlayerAction::FlyDown, KeyCode::ShiftLeft)
-        .insert(PlayerAction::Shoot, MouseButton::Left);
+        .insert(PlayerAction::Shoot, MouseButton::Left)
+        .insert(PlayerAction::Aim, MouseButton::Right)
+        .insert(PlayerAction::NextWeapon, MouseScrollDirection::DOWN)
+        .insert(PlayerAction::PrevWeapon, MouseScrollDirection::UP);
     map
 }
 
@@ -99,6 +133,8 @@ fn spawn_player(
         .spawn((
             Name::new("Player"),
             Player,
+            Health::new(PLAYER_HEALTH),
+            Shield::new(PLAYER_SHIELD, PLAYER_SHIELD_DELAY, PLAYER_SHIELD_RATE),
             SpatialBundle::default(),
             Collider::capsule(0.5, 1.),
             mesh_assets.add(Capsule3d::new(0.5, 1.)),
@@ -136,6 +172,7 @@ fn spawn_player(
                 .with_max_time_of_impact(10.)
                 .with_query_filter(SpatialQueryFilter::from_excluded_entities([player])),
             RayHits::default(),
+            SpatialListener::new(0.3),
             PlayerCam,
         ));
     });
@@ -150,7 +187,7 @@ fn player_move(
             &RigidBody,
             Option<&Grounded>,
         ),
-        With<Player>,
+        (With<Player>, Without<Dead>),
     >,
     camera: Query<&GlobalTransform, With<PlayerCam>>,
 ) {
@@ -199,7 +236,7 @@ fn player_move(
 fn player_look(
     window: Query<&Window, With<PrimaryWindow>>,
     mut player: Query<(&mut Transform, &Children, &ActionState<PlayerAction>), With<Player>>,
-    mut camera: Query<&mut Transform, (With<PlayerCam>, Without<Player>)>,
+    mut camera: Query<(&mut Transform, &Projection), (With<PlayerCam>, Without<Player>)>,
 ) {
     let window = window.single();
     if !window.focused {
@@ -211,10 +248,15 @@ fn player_look(
             error!("Player has not child entity");
             continue;
         };
-        let Ok(mut camera) = camera.get_mut(child) else {
+        let Ok((mut camera, projection)) = camera.get_mut(child) else {
             error!("first child is not camera");
             continue;
         };
+        // slow the look down by as much as the view is zoomed in
+        let scale = match projection {
+            Projection::Perspective(perspective) => scale * perspective.fov / BASE_FOV,
+            Projection::Orthographic(_) => scale,
+        };
 
         let look = actions.axis_pair(&PlayerAction::Look);
 
@@ -239,6 +281,42 @@ fn noclip(mut player: Query<&mut RigidBody, With<Player>>) {
     }
 }
 
+/// Counts down until a dead player is sent back to the spawn
+#[derive(Component)]
+struct Respawning(Timer);
+
+fn player_death(
+    mut commands: Commands,
+    mut deaths: EventReader<Death>,
+    players: Query<(), With<Player>>,
+    rules: Res<MapRules>,
+) {
+    for death in deaths.read() {
+        if players.contains(death.target) {
+            commands
+                .entity(death.target)
+                .insert(Respawning(Timer::from_seconds(
+                    rules.respawn_time,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+
+fn respawn_timer(
+    mut commands: Commands,
+    mut players: Query<(Entity, &mut Respawning)>,
+    mut respawn: EventWriter<RespawnPlayer>,
+    time: Res<Time>,
+) {
+    for (player, mut timer) in &mut players {
+        if timer.0.tick(time.delta()).finished() {
+            commands.entity(player).remove::<Respawning>();
+            respawn.send(RespawnPlayer { player, died: true });
+        }
+    }
+}
+
 #[derive(Component)]
 struct MovementDampingFactor(f32);
 
@@ -251,7 +329,7 @@ fn apply_movement_damping(mut query: Query<(&MovementDampingFactor, &mut LinearV
 }
 
 #[derive(Component)]
-struct Grounded;
+pub struct Grounded;
 
 /// Updates the [`Grounded`] status for character controllers.
 fn update_grounded(mut commands: Commands, mut query: Query<(Entity, &ShapeHits), With<Player>>) {